@@ -1,3 +1,4 @@
+use crate::audio::clock::PlaybackClock;
 use crate::audio::{AudioFetcher, AudioService};
 use crate::components::RenderableComponent;
 use crate::events::EventState;
@@ -6,10 +7,20 @@ pub(crate) use crate::state::GlobalState;
 use crate::state::{
     Focus, InputMode, SongState, get_guarded_state, get_state, has_next_song, with_state,
 };
+use crate::storage::offsets::OffsetStore;
+use crate::storage::session::{Session, SessionStore};
 use crate::util::{EMDASH, EMOJI_MARTINI};
 use crate::{
     components::{
-        help::Help, lyrics::Lyrics, queue::Queue, search::Search, timer::Timer, title::Title,
+        help::Help,
+        lyrics::Lyrics,
+        playlist::{Playlist, PlaylistAction},
+        queue::Queue,
+        restore::Restore,
+        search::Search,
+        seek::Seek,
+        timer::Timer,
+        title::Title,
     },
     events::Key,
 };
@@ -20,7 +31,12 @@ use ratatui::{
 };
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+// SEEK_STEP_MS is how far the arrow keys move the song.
+const SEEK_STEP_MS: u64 = 5_000;
+// LYRIC_OFFSET_STEP_MS is how far one nudge moves the lyrics against the audio.
+const LYRIC_OFFSET_STEP_MS: i64 = 100;
 
 pub struct AppComponent<'a, AF, AS, LF, LS>
 where
@@ -34,11 +50,19 @@ where
 
     help: Help,
     lyrics: Lyrics<LS>,
+    playlist: Playlist<LF>,
     queue: Queue,
+    restore: Restore,
     search: Search<'a, AF, LF>,
+    seek: Seek,
     timer: Timer,
 
+    clock: PlaybackClock,
     global_state: Arc<Mutex<GlobalState>>,
+    offsets: OffsetStore,
+    // pending_seek_ms is where to start the next song that plays, for resuming a restored one.
+    pending_seek_ms: Option<u64>,
+    sessions: SessionStore,
     tick_accumulator: u64,
 }
 
@@ -49,8 +73,19 @@ where
     LF: LyricsFetcher + Send + Sync + 'static,
     LS: LyricsService + Send + Sync + 'static,
 {
-    pub fn new(lf: Arc<LF>, ls: Arc<LS>, af: Arc<AF>, aus: Arc<AS>) -> Self {
+    pub fn new(lf: Arc<LF>, ls: Arc<LS>, af: Arc<AF>, aus: Arc<AS>, live_search: bool) -> Self {
         let global_state = Arc::new(Mutex::new(GlobalState::new()));
+
+        // Offer to restore whatever the last run left in the queue.
+        let (sessions, saved) = SessionStore::load();
+        let saved = match saved.is_empty() {
+            true => None,
+            false => {
+                with_state(&global_state, |s| s.focus = Focus::Restore);
+                Some(saved)
+            }
+        };
+
         Self {
             // Injected services.
             audio_service: aus.clone(),
@@ -59,12 +94,19 @@ where
             // UI Components.
             help: Help::new(),
             lyrics: Lyrics::new(global_state.clone(), ls),
+            playlist: Playlist::new(global_state.clone(), lf.clone()),
             queue: Queue::new(global_state.clone()),
-            search: Search::new(global_state.clone(), af, lf),
+            restore: Restore::new(global_state.clone(), saved),
+            search: Search::new(global_state.clone(), af, lf, live_search),
+            seek: Seek::new(global_state.clone()),
             timer: Timer::new(global_state.clone()),
 
             // State.
+            clock: PlaybackClock::new(),
             global_state: global_state.clone(),
+            offsets: OffsetStore::load(),
+            pending_seek_ms: None,
+            sessions,
             tick_accumulator: 0,
         }
     }
@@ -86,21 +128,79 @@ where
             self.tick_accumulator %= 1000;
         }
 
+        // Pick up search results and imported songs that have arrived in the background.
+        self.search.tick();
+        self.playlist.tick();
+
         // Maybe play.
         self.advance_queue();
         self.play();
 
-        // Update the timer.
-        let mut state = self.global_state.lock().unwrap();
-        if state.song_state == SongState::Playing {
-            state.current_song_elapsed_ms += tick_rate_ms;
+        // Update the song clock. Prefer the audio service's own playback position so the lyrics
+        // follow what is actually audible; fall back to wall-clock time for services that can't
+        // report one.
+        {
+            let mut state = self.global_state.lock().unwrap();
+            if state.song_state == SongState::Playing {
+                let elapsed = self
+                    .audio_service
+                    .position()
+                    .unwrap_or_else(|| self.clock.elapsed());
+
+                state.current_song_elapsed_ms = elapsed.as_millis() as u64;
+            }
+        }
+
+        self.save_session(false);
+
+        // Tell the user when saving the session starts or stops failing, rather than on every try.
+        if self.sessions.poll() {
+            let status = self
+                .sessions
+                .error()
+                .map(|e| format!("Failed to save the session: {}", e));
+            with_state(&self.global_state, |s| s.status = status);
+        }
+    }
+
+    // save_session saves the queue and the current song if they've changed, and the position in the
+    // song every so often, or straight away if forced. Nothing is saved while the last session is
+    // still on offer, so it isn't overwritten before the user can restore it.
+    fn save_session(&mut self, force: bool) {
+        if self.restore.pending() {
+            return;
+        }
+
+        // The session is taken under the lock, which is released before it's written.
+        let state = get_guarded_state(&self.global_state);
+        let session = self.sessions.snapshot(&state, force, Instant::now());
+        drop(state);
+
+        if let Some(session) = session {
+            self.sessions.save(session);
+        }
+    }
+
+    // restore_session puts a saved session back: its song plays again from where it was, followed
+    // by its queue.
+    fn restore_session(&mut self, session: Session) {
+        with_state(&self.global_state, |s| {
+            if s.current_song.is_none() {
+                s.current_song = session.current_song;
+                s.current_song_elapsed_ms = 0;
+            }
+            s.song_list.extend(session.song_list);
+        });
+
+        if session.current_song_elapsed_ms > 0 {
+            self.pending_seek_ms = Some(session.current_song_elapsed_ms);
         }
     }
 
     // advance_queue advances the next song in the queue if there is one.
     fn advance_queue(&mut self) {
         let imu_state = get_state(&self.global_state);
-        if imu_state.song_state == SongState::Playing || !imu_state.has_next_song() {
+        if imu_state.song_state != SongState::None || !imu_state.has_next_song() {
             return;
         }
 
@@ -112,71 +212,196 @@ where
         mu_state.current_song_elapsed_ms = 0;
     }
 
-    fn play(&self) {
+    fn play(&mut self) {
         let imu_state = get_state(&self.global_state);
-        if imu_state.song_state == SongState::Playing || imu_state.current_song.is_none() {
+        if imu_state.song_state != SongState::None || imu_state.current_song.is_none() {
             return;
         }
 
+        // Songs without synced lyrics still play; the lyrics pane falls back to plain lyrics.
         let song = imu_state.current_song.unwrap_or_default();
 
-        // Lock state to get the current song and lyrics map and set the state to playing.
-        let (cs, lyrics) = {
-            let mut state = get_guarded_state(&self.global_state);
-
-            let lyrics = match song.lyric_map.clone() {
-                Some(map) => map,
-                None => {
-                    panic!("No lyrics found for song");
-                }
-            };
+        // Set the song to playing, with the lyric offset saved for it last time.
+        let lyric_offset_ms = self.offsets.get(&song.video_id, &song.lyric_id);
+        with_state(&self.global_state, |s| {
+            s.song_state = SongState::Playing;
+            s.lyric_offset_ms = lyric_offset_ms;
+        });
 
-            state.song_state = SongState::Playing;
+        self.clock.start();
 
-            (song, lyrics)
-        };
+        // Resume a restored song where it left off. The player holds on to the seek until the
+        // track has buffered.
+        if let Some(elapsed_ms) = self.pending_seek_ms.take() {
+            self.seek_to(elapsed_ms);
+        }
 
-        // Clone the services and state for the threads.
+        // Clone the service and state for the audio thread.
         let aus = Arc::clone(&self.audio_service);
-        let ls = Arc::clone(&self.lyrics_service);
-        let id = cs.video_id.clone();
+        let id = song.video_id.clone();
         let au_state = self.global_state.clone();
-        let ly_state = self.global_state.clone();
+        let playback_id = with_state(&self.global_state, |s| {
+            s.playback_id += 1;
+            s.playback_id
+        });
 
         let audio_handle = thread::spawn(move || {
             aus.play(&id);
 
-            // Update play status when the song finishes.
+            // Move on when the song finishes, unless it was cut short (e.g. skipped), in which case
+            // whoever stopped it has already moved on. Stopping a song that ended by itself still
+            // ends its generation, so a seek meant for it (say, if it failed to load) can't carry
+            // over to the next one.
             let mut state = get_guarded_state(&au_state);
-            state.song_state = SongState::None;
+            if state.playback_id == playback_id {
+                aus.stop();
+                state.end_song();
+            }
         });
+    }
 
-        let lyrics_handle = thread::spawn(move || {
-            loop {
-                // Sleep to avoid busy waiting.
-                thread::sleep(Duration::from_millis(200));
-                
-                // Check if the song is still playing.
-                let imu_state = get_state(&ly_state);
-                if imu_state.song_state != SongState::Playing {
-                    return;
-                }
+    // stop_playback stops the audio and leaves nothing playing, so the next tick plays whatever
+    // is current by then.
+    fn stop_playback(&mut self) {
+        self.audio_service.stop();
 
-                let lyrics = ls
-                    .play(imu_state.current_song_elapsed_ms, lyrics.clone())
-                    .unwrap_or(Vec::new());
+        with_state(&self.global_state, |s| {
+            // The audio thread of the stopped song mustn't touch the state of the next one.
+            s.playback_id += 1;
+            s.song_state = SongState::None;
+            s.current_song_elapsed_ms = 0;
+        });
+        self.save_session(true);
+    }
 
-                if lyrics.is_empty() {
-                    return;
-                }
-                // Lock the state to update the current lyric.
-                let mut state = get_guarded_state(&ly_state);
+    // skip ends the current song early and moves on to the next one in the queue.
+    fn skip(&mut self) {
+        if get_state(&self.global_state).current_song.is_none() {
+            return;
+        }
+
+        self.stop_playback();
+        with_state(&self.global_state, |s| s.end_song());
+    }
+
+    // restart plays the current song again from the start.
+    fn restart(&mut self) {
+        if get_state(&self.global_state).current_song.is_none() {
+            return;
+        }
+
+        self.stop_playback();
+    }
+
+    // previous goes back to the song played before the current one, which returns to the front of
+    // the queue. With no previous song, the current one restarts.
+    fn previous(&mut self) {
+        let imu_state = get_state(&self.global_state);
+        if imu_state.history.is_empty() {
+            self.restart();
+            return;
+        }
+
+        self.stop_playback();
+        with_state(&self.global_state, |s| {
+            if let Some(current) = s.current_song.take() {
+                s.song_list.insert(0, current);
+            }
+            s.current_song = s.history.pop();
+        });
+    }
+
+    // shutdown saves the session and stops any audio that is still playing before the app exits.
+    // The session is written before it returns, and a failure is left for the caller to report
+    // once the screen is back to normal.
+    pub(crate) fn shutdown(&mut self) -> anyhow::Result<()> {
+        let saved = match self.restore.pending() {
+            true => Ok(()),
+            false => {
+                let session = Session::from_state(&get_guarded_state(&self.global_state));
+                self.sessions.save_now(session)
+            }
+        };
+        self.audio_service.stop();
 
-                state.current_lyrics = lyrics.clone();
+        saved
+    }
+
+    // toggle_pause pauses or resumes the current song. The lyric clock only advances while the
+    // song is playing, so pausing the audio also freezes the lyrics.
+    fn toggle_pause(&mut self) {
+        let song_state = get_state(&self.global_state).song_state;
+
+        match song_state {
+            SongState::Playing => {
+                self.audio_service.pause();
+                self.clock.pause();
+                with_state(&self.global_state, |s| {
+                    s.song_state = SongState::Paused;
+                });
+                self.save_session(true);
             }
+            SongState::Paused => {
+                self.audio_service.resume();
+                self.clock.resume();
+                with_state(&self.global_state, |s| {
+                    s.song_state = SongState::Playing;
+                });
+            }
+            SongState::None => {}
+        }
+    }
+
+    // seek_to moves the song to elapsed_ms. The lyrics follow on the next frame.
+    fn seek_to(&mut self, elapsed_ms: u64) {
+        let imu_state = get_state(&self.global_state);
+        if imu_state.song_state == SongState::None {
+            return;
+        }
+
+        let song = imu_state.current_song.unwrap_or_default();
+        let elapsed_ms = match song.duration_ms {
+            0 => elapsed_ms,
+            duration_ms => elapsed_ms.min(duration_ms),
+        };
+
+        self.audio_service.seek(Duration::from_millis(elapsed_ms));
+        self.clock.seek(Duration::from_millis(elapsed_ms));
+
+        with_state(&self.global_state, |s| {
+            s.current_song_elapsed_ms = elapsed_ms;
         });
     }
 
+    // nudge_lyrics shifts the lyrics earlier (positive) or later (negative) by offset_ms, and
+    // remembers the new offset for this pairing of audio and lyrics.
+    fn nudge_lyrics(&mut self, offset_ms: i64) {
+        let imu_state = get_state(&self.global_state);
+        let song = match imu_state.current_song {
+            Some(song) if imu_state.song_state != SongState::None => song,
+            _ => return,
+        };
+
+        let lyric_offset_ms = imu_state.lyric_offset_ms + offset_ms;
+        with_state(&self.global_state, |s| {
+            s.lyric_offset_ms = lyric_offset_ms;
+        });
+
+        if let Err(e) = self
+            .offsets
+            .set(&song.video_id, &song.lyric_id, lyric_offset_ms)
+        {
+            println!("Failed to save lyric offset: {}", e);
+        }
+    }
+
+    // seek_by moves the song forward (or backward, for negative offsets) by offset_ms.
+    fn seek_by(&mut self, offset_ms: i64) {
+        let elapsed_ms = get_state(&self.global_state).current_song_elapsed_ms;
+
+        self.seek_to(elapsed_ms.saturating_add_signed(offset_ms));
+    }
+
     // event handles keystrokes and updates the state of the application.
     //
     // This is organized by "focus" (the component that is currently active). Child components
@@ -197,6 +422,12 @@ where
                             s.focus = Focus::Home;
                         });
                     }
+                    Key::Char('i') => {
+                        self.playlist.open(PlaylistAction::Import);
+                    }
+                    Key::Char('x') => {
+                        self.playlist.open(PlaylistAction::Export);
+                    }
                     Key::Char('/') => {
                         with_state(&self.global_state, |s| {
                             s.focus = Focus::Search;
@@ -220,6 +451,29 @@ where
                     _ => {}
                 }
             }
+            Focus::Seek => {
+                if self.seek.event(key).await.unwrap().is_consumed() {
+                    if let Some(elapsed_ms) = self.seek.take_target() {
+                        self.seek_to(elapsed_ms);
+                    }
+
+                    return Ok(EventState::Consumed);
+                }
+            }
+            Focus::Playlist => {
+                if self.playlist.event(key).await.unwrap().is_consumed() {
+                    return Ok(EventState::Consumed);
+                }
+            }
+            Focus::Restore => {
+                if self.restore.event(key).await.unwrap().is_consumed() {
+                    if let Some(session) = self.restore.take_accepted() {
+                        self.restore_session(session);
+                    }
+
+                    return Ok(EventState::Consumed);
+                }
+            }
             Focus::Help => match key {
                 Key::Esc | Key::Char('h') => {
                     with_state(&self.global_state, |s| {
@@ -235,7 +489,34 @@ where
                     });
                 }
                 Key::Char(' ') => {
-                    // TODO: play/pause
+                    self.toggle_pause();
+                }
+                Key::Left => {
+                    self.seek_by(-(SEEK_STEP_MS as i64));
+                }
+                Key::Right => {
+                    self.seek_by(SEEK_STEP_MS as i64);
+                }
+                Key::Char('n') => {
+                    self.skip();
+                }
+                Key::Char('r') => {
+                    self.restart();
+                }
+                Key::Char('p') => {
+                    self.previous();
+                }
+                Key::Char('[') => {
+                    self.nudge_lyrics(LYRIC_OFFSET_STEP_MS);
+                }
+                Key::Char(']') => {
+                    self.nudge_lyrics(-LYRIC_OFFSET_STEP_MS);
+                }
+                Key::Char('g') if get_state(&self.global_state).song_state != SongState::None => {
+                    with_state(&self.global_state, |s| {
+                        s.focus = Focus::Seek;
+                        s.mode = InputMode::Input;
+                    });
                 }
                 Key::Char('h') => {
                     with_state(&self.global_state, |s| {
@@ -286,7 +567,7 @@ where
         // The layout of the body is determined by focus.
         let focus = get_state(&self.global_state).focus.clone();
         match focus {
-            Focus::Queue => {
+            Focus::Queue | Focus::Playlist => {
                 let inner_rects = Layout::default()
                     .direction(Direction::Horizontal)
                     .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
@@ -310,6 +591,15 @@ where
             Focus::Help => {
                 self.help.render::<B>(f, footer)?;
             }
+            Focus::Playlist => {
+                self.playlist.render::<B>(f, footer)?;
+            }
+            Focus::Restore => {
+                self.restore.render::<B>(f, footer)?;
+            }
+            Focus::Seek => {
+                self.seek.render::<B>(f, footer)?;
+            }
             _ => {
                 self.timer.render::<B>(f, footer)?;
             }
@@ -2,7 +2,11 @@ use async_trait::async_trait;
 use std::error::Error;
 use std::time::Duration;
 
+pub(crate) mod clock;
+pub(crate) mod local;
+pub(crate) mod player;
 pub(crate) mod youtube;
+pub(crate) mod ytdlp;
 mod platform;
 
 #[derive(Debug, Clone)]
@@ -13,12 +17,45 @@ pub struct AudioResult {
     pub duration: Duration,
 }
 
+// AudioPage is one page of search results, along with the token for the next page if there is one.
+#[derive(Debug, Clone, Default)]
+pub struct AudioPage {
+    pub results: Vec<AudioResult>,
+    pub next_page: Option<String>,
+}
+
 #[async_trait]
 pub trait AudioFetcher {
     async fn search(&self, query: &str) -> anyhow::Result<Vec<AudioResult>>;
+
+    // search_page fetches the page of results for query that page_token points to, or the first
+    // page without one. Fetchers that don't page return all their results as a single page.
+    async fn search_page(
+        &self,
+        query: &str,
+        page_token: Option<&str>,
+    ) -> anyhow::Result<AudioPage> {
+        if page_token.is_some() {
+            return Ok(AudioPage::default());
+        }
+
+        Ok(AudioPage {
+            results: self.search(query).await?,
+            next_page: None,
+        })
+    }
 }
 
 pub trait AudioService: Send + Sync {
     fn play(&self, id: &str);
     fn pause(&self);
+    fn resume(&self);
+    fn stop(&self);
+    fn seek(&self, position: Duration);
+
+    // position reports how far into the current track playback is. Services that can't tell
+    // return None, and callers should keep time themselves.
+    fn position(&self) -> Option<Duration> {
+        None
+    }
 }
\ No newline at end of file
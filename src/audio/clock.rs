@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+// PlaybackClock measures how far into a song we are using wall-clock time. It is the fallback
+// for audio services that can't report their playback position, and unlike counting ticks it
+// doesn't drift when the event loop stalls.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PlaybackClock {
+    // base is the elapsed time banked before the clock was last started.
+    base: Duration,
+    // started is when the clock was last started, or None while it is stopped.
+    started: Option<Instant>,
+}
+
+// Each method has an _at counterpart that takes the current time, so tests can control it.
+impl PlaybackClock {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    // start resets the clock to zero and starts it running.
+    pub(crate) fn start(&mut self) {
+        self.start_at(Instant::now());
+    }
+
+    pub(crate) fn pause(&mut self) {
+        self.pause_at(Instant::now());
+    }
+
+    pub(crate) fn resume(&mut self) {
+        self.resume_at(Instant::now());
+    }
+
+    // seek sets the elapsed time without changing whether the clock is running.
+    pub(crate) fn seek(&mut self, position: Duration) {
+        self.seek_at(position, Instant::now());
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.elapsed_at(Instant::now())
+    }
+
+    fn start_at(&mut self, now: Instant) {
+        self.base = Duration::ZERO;
+        self.started = Some(now);
+    }
+
+    fn pause_at(&mut self, now: Instant) {
+        if let Some(started) = self.started.take() {
+            self.base += now.saturating_duration_since(started);
+        }
+    }
+
+    fn resume_at(&mut self, now: Instant) {
+        if self.started.is_none() {
+            self.started = Some(now);
+        }
+    }
+
+    fn seek_at(&mut self, position: Duration, now: Instant) {
+        self.base = position;
+        if self.started.is_some() {
+            self.started = Some(now);
+        }
+    }
+
+    fn elapsed_at(&self, now: Instant) -> Duration {
+        match self.started {
+            Some(started) => self.base + now.saturating_duration_since(started),
+            None => self.base,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn runs_from_zero_once_started() {
+        let t0 = Instant::now();
+        let mut clock = PlaybackClock::new();
+        assert_eq!(clock.elapsed_at(t0), Duration::ZERO);
+
+        clock.start_at(t0);
+
+        assert_eq!(clock.elapsed_at(t0 + secs(3)), secs(3));
+    }
+
+    #[test]
+    fn pausing_freezes_the_time() {
+        let t0 = Instant::now();
+        let mut clock = PlaybackClock::new();
+        clock.start_at(t0);
+
+        clock.pause_at(t0 + secs(5));
+
+        assert_eq!(clock.elapsed_at(t0 + secs(5)), secs(5));
+        assert_eq!(clock.elapsed_at(t0 + secs(60)), secs(5));
+    }
+
+    #[test]
+    fn resuming_continues_from_the_frozen_time() {
+        let t0 = Instant::now();
+        let mut clock = PlaybackClock::new();
+        clock.start_at(t0);
+        clock.pause_at(t0 + secs(5));
+
+        clock.resume_at(t0 + secs(20));
+        clock.resume_at(t0 + secs(25));
+
+        assert_eq!(clock.elapsed_at(t0 + secs(30)), secs(15));
+    }
+
+    #[test]
+    fn seeking_while_paused_stays_paused() {
+        let t0 = Instant::now();
+        let mut clock = PlaybackClock::new();
+        clock.start_at(t0);
+        clock.pause_at(t0 + secs(5));
+
+        clock.seek_at(secs(90), t0 + secs(10));
+
+        assert_eq!(clock.elapsed_at(t0 + secs(30)), secs(90));
+        clock.resume_at(t0 + secs(30));
+        assert_eq!(clock.elapsed_at(t0 + secs(32)), secs(92));
+    }
+
+    #[test]
+    fn seeking_while_running_keeps_running() {
+        let t0 = Instant::now();
+        let mut clock = PlaybackClock::new();
+        clock.start_at(t0);
+
+        clock.seek_at(secs(90), t0 + secs(10));
+
+        assert_eq!(clock.elapsed_at(t0 + secs(10)), secs(90));
+        assert_eq!(clock.elapsed_at(t0 + secs(14)), secs(94));
+    }
+
+    #[test]
+    fn starting_again_resets_to_zero() {
+        let t0 = Instant::now();
+        let mut clock = PlaybackClock::new();
+        clock.start_at(t0);
+        clock.seek_at(secs(90), t0 + secs(1));
+
+        clock.start_at(t0 + secs(2));
+
+        assert_eq!(clock.elapsed_at(t0 + secs(3)), secs(1));
+    }
+}
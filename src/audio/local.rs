@@ -0,0 +1,315 @@
+use crate::audio::player::Player;
+use crate::audio::{AudioFetcher, AudioResult, AudioService};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
+use symphonia::core::probe::{Hint, ProbeResult};
+use symphonia::core::units::TimeBase;
+
+const AUDIO_EXTENSIONS: [&str; 4] = ["mp3", "ogg", "flac", "wav"];
+const MAX_RESULTS: usize = 25;
+
+#[derive(Debug, Clone, Default)]
+struct LocalTrack {
+    // id is the track's path relative to the library root.
+    id: String,
+    title: String,
+    artist: String,
+    album: String,
+    duration: Duration,
+    // haystack is the lowercased text searches are matched against.
+    haystack: String,
+}
+
+// LocalLibrary serves audio from a directory of local files, e.g. a folder of karaoke backing
+// tracks. The directory is indexed once, when the library is created. Anything in it that can't be
+// read is skipped, so a missing or broken library leaves the app running with no tracks.
+#[derive(Debug, Clone)]
+pub struct LocalLibrary {
+    root: PathBuf,
+    tracks: Arc<Vec<LocalTrack>>,
+    // durations holds the durations of tracks whose headers don't give one, by id. They take a
+    // pass over the whole file, so they're worked out in the background after indexing.
+    durations: Arc<Mutex<HashMap<String, Duration>>>,
+    player: Arc<Player>,
+}
+
+impl LocalLibrary {
+    pub(crate) fn new(root: PathBuf, player: Arc<Player>) -> Self {
+        let mut tracks = Vec::new();
+        index_dir(&root, &root, &mut tracks);
+        tracks.sort_by(|a, b| (&a.artist, &a.title).cmp(&(&b.artist, &b.title)));
+
+        let durations = Arc::new(Mutex::new(HashMap::new()));
+        let unknown: Vec<String> = tracks
+            .iter()
+            .filter(|track| track.duration.is_zero())
+            .map(|track| track.id.clone())
+            .collect();
+        if !unknown.is_empty() {
+            let (root, durations) = (root.clone(), durations.clone());
+            std::thread::spawn(move || {
+                for id in unknown {
+                    if let Some(duration) = scan_duration(&root.join(&id)) {
+                        durations.lock().expect("Failed to lock durations").insert(id, duration);
+                    }
+                }
+            });
+        }
+
+        Self {
+            root,
+            tracks: Arc::new(tracks),
+            durations,
+            player,
+        }
+    }
+
+    // duration is the track's duration from its header, or from the background scan once that has
+    // got to it. It's zero until then.
+    fn duration(&self, track: &LocalTrack) -> Duration {
+        match track.duration.is_zero() {
+            true => self
+                .durations
+                .lock()
+                .expect("Failed to lock durations")
+                .get(&track.id)
+                .copied()
+                .unwrap_or_default(),
+            false => track.duration,
+        }
+    }
+}
+
+// index_dir walks dir recursively, adding every audio file it finds to tracks. Directories and
+// entries that can't be read are skipped. Symlinks aren't followed into directories, since they
+// can loop back on themselves.
+fn index_dir(root: &Path, dir: &Path, tracks: &mut Vec<LocalTrack>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            println!("Skipping {}: {}", dir.display(), e);
+            return;
+        }
+    };
+
+    for entry in entries {
+        let entry = entry.and_then(|entry| Ok((entry.path(), entry.file_type()?)));
+        let (path, file_type) = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                println!("Skipping an entry of {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+
+        // file_type doesn't follow symlinks, so a symlinked directory isn't a directory here.
+        if file_type.is_dir() {
+            index_dir(root, &path, tracks);
+            continue;
+        }
+
+        let is_audio = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if !is_audio {
+            continue;
+        }
+
+        let id = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+
+        match read_track(&path) {
+            Ok(mut track) => {
+                track.id = id;
+                tracks.push(track);
+            }
+            Err(e) => {
+                println!("Failed to read {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+fn probe(path: &Path) -> anyhow::Result<ProbeResult> {
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mss = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    Ok(symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?)
+}
+
+// read_track reads the tags of the audio file at path, and its duration if the header gives one.
+// Files without tags fall back to their file name for the title.
+fn read_track(path: &Path) -> anyhow::Result<LocalTrack> {
+    let mut probed = probe(path)?;
+
+    let mut track = LocalTrack::default();
+
+    // Tags may come before the container (e.g. ID3v2 on mp3) or from the container itself (e.g.
+    // Vorbis comments on ogg and flac).
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        apply_tags(&mut track, revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_tags(&mut track, revision);
+    }
+
+    if track.title.is_empty() {
+        track.title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+
+    track.duration = header_duration(probed.format.as_ref()).unwrap_or_default();
+    track.haystack = format!(
+        "{} {} {} {}",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        track.title,
+        track.artist,
+        track.album,
+    )
+    .to_lowercase();
+
+    Ok(track)
+}
+
+fn apply_tags(track: &mut LocalTrack, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => track.title = tag.value.to_string(),
+            Some(StandardTagKey::Artist) => track.artist = tag.value.to_string(),
+            Some(StandardTagKey::Album) => track.album = tag.value.to_string(),
+            _ => {}
+        }
+    }
+}
+
+// header_duration is the duration from the frame count in the file header, if it has one (e.g. not
+// mp3 without a Xing header).
+fn header_duration(format: &dyn FormatReader) -> Option<Duration> {
+    let track = format.default_track()?;
+    let frames = track.codec_params.n_frames?;
+
+    to_duration(track.codec_params.time_base?, frames)
+}
+
+// scan_duration works out the duration of the audio file at path by adding up the duration of
+// every packet. It doesn't decode them, but it does read the whole file.
+fn scan_duration(path: &Path) -> Option<Duration> {
+    let mut format = probe(path).ok()?.format;
+    let track = format.default_track()?;
+    let (track_id, time_base) = (track.id, track.codec_params.time_base?);
+
+    let mut frames = 0;
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() == track_id {
+            frames += packet.dur;
+        }
+    }
+
+    to_duration(time_base, frames)
+}
+
+fn to_duration(time_base: TimeBase, frames: u64) -> Option<Duration> {
+    let time = time_base.calc_time(frames);
+    Some(Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac))
+}
+
+#[async_trait]
+impl AudioFetcher for LocalLibrary {
+    async fn search(&self, query: &str) -> anyhow::Result<Vec<AudioResult>> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect();
+
+        let results = self
+            .tracks
+            .iter()
+            .filter(|track| terms.iter().all(|term| track.haystack.contains(term)))
+            .take(MAX_RESULTS)
+            .map(|track| AudioResult {
+                id: track.id.clone(),
+                title: track.title.clone(),
+                artist: track.artist.clone(),
+                duration: self.duration(track),
+            })
+            .collect();
+
+        Ok(results)
+    }
+}
+
+impl AudioService for LocalLibrary {
+    fn play(&self, id: &str) {
+        if let Err(e) = self.player.play_file(&self.root.join(id)) {
+            println!("Failed to play {}: {}", id, e);
+        }
+    }
+
+    fn pause(&self) {
+        self.player.pause();
+    }
+
+    fn resume(&self) {
+        self.player.resume();
+    }
+
+    fn stop(&self) {
+        self.player.stop();
+    }
+
+    fn seek(&self, position: Duration) {
+        if let Err(e) = self.player.seek(position) {
+            println!("Failed to seek: {}", e);
+        }
+    }
+
+    fn position(&self) -> Option<Duration> {
+        Some(self.player.position())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_what_it_cannot_read_and_symlink_loops() {
+        let root = std::env::temp_dir().join(format!("cliraoke-library-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("Queen")).unwrap();
+        std::fs::write(root.join("Queen/notes.txt"), "not audio").unwrap();
+        std::fs::write(root.join("Queen/broken.mp3"), "not audio either").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("Queen/loop")).unwrap();
+
+        let mut tracks = Vec::new();
+        index_dir(&root, &root, &mut tracks);
+        index_dir(&root, &root.join("missing"), &mut tracks);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert!(tracks.is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::anyhow;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, mpsc};
+use std::thread;
+use std::time::Duration;
+use stream_download::storage::temp::TempStorageProvider;
+use stream_download::{Settings, StreamDownload};
+use tokio::runtime::Handle;
+
+// Player is the in-process audio engine. It decodes one track at a time to the default output
+// device and is controlled through its methods, so a single Player can be shared (via Arc) by every
+// audio service that needs to make noise.
+pub(crate) struct Player {
+    output: OutputStreamHandle,
+    runtime: Handle,
+    sink: Mutex<Option<Arc<Sink>>>,
+    // generation is bumped every time playback is stopped, so a track that is still buffering when
+    // it is cancelled knows not to start once it is ready.
+    generation: AtomicU64,
+    paused: AtomicBool,
+    // pending_seek holds a seek requested while the track was still buffering, with the generation
+    // it was requested in. Only that generation's track applies it.
+    pending_seek: Mutex<Option<(u64, Duration)>>,
+    // Dropping the sender releases the output device held by the output thread.
+    _shutdown: mpsc::Sender<()>,
+}
+
+impl Player {
+    // new opens the default output device. It must be called from within a tokio runtime, which is
+    // used to drive streaming downloads.
+    pub(crate) fn new() -> anyhow::Result<Self> {
+        let (output_tx, output_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>();
+
+        // rodio's OutputStream can't be moved between threads, so it lives on its own thread for as
+        // long as the Player does. Everything else talks to it through the (shareable) handle.
+        thread::spawn(move || match OutputStream::try_default() {
+            Ok((_stream, handle)) => {
+                let _ = output_tx.send(Ok(handle));
+                let _ = shutdown_rx.recv();
+            }
+            Err(e) => {
+                let _ = output_tx.send(Err(e));
+            }
+        });
+
+        let output = output_rx
+            .recv()
+            .map_err(|_| anyhow!("Audio output thread exited"))??;
+
+        Ok(Self {
+            output,
+            runtime: Handle::try_current()?,
+            sink: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            paused: AtomicBool::new(false),
+            pending_seek: Mutex::new(None),
+            _shutdown: shutdown_tx,
+        })
+    }
+
+    // generation identifies the current playback. Services that do slow work before calling
+    // play_url, like looking up a stream URL, take it first so that a stop during that work still
+    // cancels the track.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    // play_url streams and plays the audio at url, unless playback has been stopped since
+    // generation was taken. It blocks until the track finishes or playback is stopped.
+    pub(crate) fn play_url(&self, url: &str, generation: u64) -> anyhow::Result<()> {
+        let reader = self
+            .runtime
+            .block_on(StreamDownload::new_http(
+                url.parse()?,
+                TempStorageProvider::new(),
+                Settings::default(),
+            ))
+            .map_err(|e| anyhow!("Failed to open audio stream: {}", e))?;
+
+        self.play_reader(reader, generation)
+    }
+
+    // play_file plays a local audio file. It blocks until the track finishes or playback is
+    // stopped.
+    pub(crate) fn play_file(&self, path: &Path) -> anyhow::Result<()> {
+        let generation = self.generation.load(Ordering::SeqCst);
+        let file = BufReader::new(File::open(path)?);
+
+        self.play_reader(file, generation)
+    }
+
+    fn play_reader<R>(&self, reader: R, generation: u64) -> anyhow::Result<()>
+    where
+        R: Read + Seek + Send + Sync + 'static,
+    {
+        let source = Decoder::new(reader)?;
+        let sink = Arc::new(Sink::try_new(&self.output)?);
+        if self.paused.load(Ordering::SeqCst) {
+            sink.pause();
+        }
+        sink.append(source);
+
+        {
+            let mut current = self.current_sink();
+
+            // Playback was stopped while we were buffering; dropping the sink silences it.
+            if self.generation.load(Ordering::SeqCst) != generation {
+                return Ok(());
+            }
+
+            if let Some(previous) = current.replace(sink.clone()) {
+                previous.stop();
+            }
+        }
+
+        let pending_seek = self.pending_seek.lock().expect("Failed to lock seek").take();
+        let pending_seek = pending_seek.filter(|(requested, _)| *requested == generation);
+        if let Some(Err(e)) = pending_seek.map(|(_, position)| sink.try_seek(position)) {
+            println!("Failed to seek: {}", e);
+        }
+
+        sink.sleep_until_end();
+
+        // Only clear the sink if another track hasn't replaced it in the meantime.
+        let mut current = self.current_sink();
+        if current.as_ref().is_some_and(|s| Arc::ptr_eq(s, &sink)) {
+            *current = None;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        if let Some(sink) = self.current_sink().as_ref() {
+            sink.pause();
+        }
+    }
+
+    pub(crate) fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        if let Some(sink) = self.current_sink().as_ref() {
+            sink.play();
+        }
+    }
+
+    // stop ends the current track, or cancels it if it is still buffering.
+    pub(crate) fn stop(&self) {
+        let mut current = self.current_sink();
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+        *self.pending_seek.lock().expect("Failed to lock seek") = None;
+        if let Some(sink) = current.take() {
+            sink.stop();
+        }
+    }
+
+    // seek moves playback to position. If the track is still buffering, the seek is applied as
+    // soon as it starts; if it never does, stop drops the seek, so it can't carry over to the next
+    // track.
+    pub(crate) fn seek(&self, position: Duration) -> anyhow::Result<()> {
+        match self.current_sink().as_ref() {
+            Some(sink) => sink
+                .try_seek(position)
+                .map_err(|e| anyhow!("Failed to seek: {}", e)),
+            None => {
+                let generation = self.generation.load(Ordering::SeqCst);
+                let mut pending_seek = self.pending_seek.lock().expect("Failed to lock seek");
+                *pending_seek = Some((generation, position));
+                Ok(())
+            }
+        }
+    }
+
+    // position is how far into the current track playback is. It stays at zero while a track is
+    // buffering, so callers don't run ahead of the audio.
+    pub(crate) fn position(&self) -> Duration {
+        self.current_sink()
+            .as_ref()
+            .map(|sink| sink.get_pos())
+            .unwrap_or_default()
+    }
+
+    fn current_sink(&self) -> MutexGuard<'_, Option<Arc<Sink>>> {
+        self.sink.lock().expect("Failed to lock audio sink")
+    }
+}
+
+impl fmt::Debug for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Player")
+            .field("generation", &self.generation)
+            .field("paused", &self.paused)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,79 +1,60 @@
-use crate::audio::{AudioFetcher, AudioResult, AudioService};
+use crate::audio::player::Player;
+use crate::audio::ytdlp;
+use crate::audio::{AudioFetcher, AudioPage, AudioResult, AudioService};
 use anyhow::anyhow;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::error::Error;
-use std::process::Command;
+use std::sync::Arc;
 use std::time::Duration;
 
-const SEARCH_SUFFIX: &str = "karaoke version";
+pub(crate) const SEARCH_SUFFIX: &str = "karaoke version";
+// DEFAULT_PAGE_SIZE is how many results a page of search results has unless configured otherwise.
+pub(crate) const DEFAULT_PAGE_SIZE: u32 = 5;
+// MAX_PAGE_SIZE is the most results the YouTube API returns per page.
+const MAX_PAGE_SIZE: u32 = 50;
 
 #[derive(Debug, Clone)]
 pub struct YouTube {
     pub api_key: String,
     pub http_ct: Client,
+    // ytdlp_binary is the yt-dlp executable that finds the stream URL of a video to play.
+    ytdlp_binary: String,
+    page_size: u32,
+    player: Arc<Player>,
 }
 
 impl YouTube {
-    pub(crate) fn new(api_key: String) -> Self {
+    pub(crate) fn new(
+        api_key: String,
+        ytdlp_binary: String,
+        player: Arc<Player>,
+        page_size: u32,
+    ) -> Self {
         YouTube {
             api_key,
             http_ct: Client::new(),
+            ytdlp_binary,
+            page_size: page_size.clamp(1, MAX_PAGE_SIZE),
+            player,
         }
     }
 
-    fn get_url(&self, id: &str) -> Option<String> {
-        let url = format!("https://www.youtube.com/watch?v={}", id);
-
-        let output = Command::new("yt-dlp")
-            .args([
-                "-f",
-                "bestaudio",
-                "--get-url",
-                "--extract-audio",
-                "--audio-format",
-                "mp3",
-                &url,
-            ])
-            .output()
-            .expect("Failed to execute yt-dlp");
-
-        if output.status.success() {
-            let audio_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            Some(audio_url)
-        } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            eprintln!("yt-dlp failed: {}", error);
-
-            // Try a different approach - maybe without audio format specification
-            println!("Retrying with simplified parameters...");
-            let retry_output = Command::new("yt-dlp")
-                .args(["-f", "bestaudio", "--get-url", &url])
-                .output()
-                .expect("Failed to execute yt-dlp");
-
-            if retry_output.status.success() {
-                let audio_url = String::from_utf8_lossy(&retry_output.stdout)
-                    .trim()
-                    .to_string();
-                println!("Retry successful. Got audio URL: {}", audio_url);
-                Some(audio_url)
-            } else {
-                eprintln!(
-                    "Retry also failed: {}",
-                    String::from_utf8_lossy(&retry_output.stderr)
-                );
-                None
-            }
+    // get_durations looks up the durations of the videos with the given ids in one request. Videos
+    // that are missing from the response (e.g. deleted ones) or whose duration can't be parsed are
+    // left out of the map.
+    async fn get_durations(&self, ids: &[String]) -> anyhow::Result<HashMap<String, Duration>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
         }
-    }
 
-    async fn get_duration(&self, id: String) -> anyhow::Result<Duration> {
         // Build the API request URL
         let url = format!(
             "https://www.googleapis.com/youtube/v3/videos?key={}&id={}&part=contentDetails",
-            self.api_key, id,
+            self.api_key,
+            ids.join(","),
         );
 
         let res = self
@@ -94,12 +75,14 @@ impl YouTube {
 
         #[derive(Debug, serde::Deserialize)]
         struct YTVideoResponse {
+            #[serde(default)]
             items: Vec<YTVideoItem>,
         }
 
         #[derive(Debug, serde::Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct YTVideoItem {
+            id: String,
             content_details: YTContentDetails,
         }
 
@@ -109,41 +92,55 @@ impl YouTube {
         }
 
         let video_response = serde_json::from_value::<YTVideoResponse>(json)?;
-        let duration_str = &video_response.items[0].content_details.duration;
 
-        // Parse as ISO 8601 duration: https://developers.google.com/youtube/v3/docs/videos/list
-        let duration = duration_str
-            .parse::<iso8601_duration::Duration>()
-            .unwrap()
-            .to_std();
+        let durations = video_response
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                let duration_str = item.content_details.duration;
+
+                // Parse as ISO 8601 duration: https://developers.google.com/youtube/v3/docs/videos/list
+                let duration = duration_str
+                    .parse::<iso8601_duration::Duration>()
+                    .ok()
+                    .and_then(|duration| duration.to_std());
+
+                match duration {
+                    Some(duration) => Some((item.id, duration)),
+                    None => {
+                        println!("Failed to parse duration: {}", duration_str);
+                        None
+                    }
+                }
+            })
+            .collect();
 
-        match duration {
-            Some(duration) => Ok(duration),
-            None => {
-                println!("Failed to parse duration: {}", duration_str);
-                Err(anyhow!("Failed to parse duration"))
-            }
-        }
+        Ok(durations)
     }
 }
 
 #[async_trait]
 impl AudioFetcher for YouTube {
     async fn search(&self, query: &str) -> anyhow::Result<Vec<AudioResult>> {
+        Ok(self.search_page(query, None).await?.results)
+    }
+
+    async fn search_page(
+        &self,
+        query: &str,
+        page_token: Option<&str>,
+    ) -> anyhow::Result<AudioPage> {
         if query.is_empty() {
-            return Ok(Vec::new());
+            return Ok(AudioPage::default());
         }
 
-        let page_token = String::new(); // Token to handle pagination
-        let max_results = 5; // Maximum number of results per page
-
         // Build the API request URL
         let url = format!(
             "https://www.googleapis.com/youtube/v3/search?key={}&q={}&part=snippet,id&order=relevance&maxResults={}&type=video&pageToken={}",
             self.api_key,
             format!("{} {}", query, SEARCH_SUFFIX),
-            max_results,
-            page_token
+            self.page_size,
+            page_token.unwrap_or_default()
         );
 
         let response = self
@@ -179,82 +176,81 @@ impl AudioFetcher for YouTube {
             snippet: YtSnippet,
         }
         #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
         struct YoutubeResponse {
             items: Vec<YoutubeItem>,
+            next_page_token: Option<String>,
         }
 
         let json_response = serde_json::from_value::<YoutubeResponse>(json)?;
-        let futures: Vec<_> = json_response
+
+        // Look up the durations of the whole page at once.
+        let ids: Vec<String> = json_response
             .items
             .iter()
-            .map(|item| {
-                let video_id = item.id.video_id.to_owned();
-                let title = item.snippet.title.to_owned();
-                let artist = item.snippet.channel_title.to_owned();
-
-                async move {
-                    let mut res = AudioResult {
-                        id: video_id.clone(),
-                        title,
-                        artist,
-                        duration: Duration::new(0, 0), // Placeholder for duration
-                    };
-
-                    let d = self.get_duration(video_id).await;
-                    match d {
-                        Ok(duration) => {
-                            res.duration = duration;
-                        }
-                        Err(e) => {
-                            println!("Failed to get duration: {}", e);
-                            res.duration = Duration::new(0, 0); // Default value
-                        }
-                    }
+            .map(|item| item.id.video_id.to_owned())
+            .collect();
+        let durations = self.get_durations(&ids).await.unwrap_or_else(|e| {
+            println!("Failed to get durations: {}", e);
+            HashMap::new()
+        });
 
-                    res
-                }
+        let audios = json_response
+            .items
+            .into_iter()
+            .map(|item| AudioResult {
+                duration: durations
+                    .get(&item.id.video_id)
+                    .copied()
+                    .unwrap_or_default(),
+                id: item.id.video_id,
+                title: item.snippet.title,
+                artist: item.snippet.channel_title,
             })
             .collect();
 
-        let audios = futures::future::join_all(futures).await;
-
-        Ok(audios)
+        Ok(AudioPage {
+            results: audios,
+            next_page: json_response.next_page_token,
+        })
     }
 }
 
 impl AudioService for YouTube {
     fn play(&self, id: &str) {
-        let url =  self.get_url(id).expect("Failed to get url");
+        let generation = self.player.generation();
+        let url = match ytdlp::stream_url(&self.ytdlp_binary, id) {
+            Some(url) => url,
+            None => {
+                println!("Failed to get url for {}", id);
+                return;
+            }
+        };
 
-        // Create a Command to run ffplay with silenced output
-        let mut cmd = Command::new("ffplay");
+        if let Err(e) = self.player.play_url(&url, generation) {
+            println!("Failed to play audio: {}", e);
+        }
+    }
 
-        // Add arguments
-        cmd.args(["-nodisp", "-autoexit", "-loglevel", "quiet", url.as_str()]);
+    fn pause(&self) {
+        self.player.pause();
+    }
 
-        // Redirect stdout and stderr to /dev/null (on Unix) or NUL (on Windows)
-        #[cfg(target_family = "unix")]
-        {
-            cmd.stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null());
-        }
+    fn resume(&self) {
+        self.player.resume();
+    }
 
-        #[cfg(target_family = "windows")]
-        {
-            use std::process::Stdio;
-            cmd.stdout(Stdio::null()).stderr(Stdio::null());
-        }
+    fn stop(&self) {
+        self.player.stop();
+    }
 
-        // Run the command
-        match cmd.status() {
-            Ok(status) => {}
-            Err(e) => {
-                println!("Failed to run ffplay: {}", e);
-            }
+    fn seek(&self, position: Duration) {
+        if let Err(e) = self.player.seek(position) {
+            println!("Failed to seek: {}", e);
         }
     }
 
-    fn pause(&self) {
-        todo!()
+    fn position(&self) -> Option<Duration> {
+        Some(self.player.position())
     }
-}
\ No newline at end of file
+}
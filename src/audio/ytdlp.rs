@@ -0,0 +1,327 @@
+use crate::audio::player::Player;
+use crate::audio::youtube::SEARCH_SUFFIX;
+use crate::audio::{AudioFetcher, AudioPage, AudioResult, AudioService};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+// DEFAULT_BINARY is the yt-dlp executable looked up on PATH unless another one is configured.
+pub(crate) const DEFAULT_BINARY: &str = "yt-dlp";
+// Prefer m4a (AAC) audio, which the player can decode, over YouTube's default of webm (Opus).
+const AUDIO_FORMAT: &str = "bestaudio[ext=m4a]/bestaudio";
+
+// YtDlp searches YouTube through yt-dlp instead of the YouTube Data API, so it needs no API key
+// and costs no quota. It is slower than the API, since every search starts a yt-dlp process.
+#[derive(Debug, Clone)]
+pub struct YtDlp {
+    binary: String,
+    page_size: u32,
+    player: Arc<Player>,
+}
+
+impl YtDlp {
+    pub(crate) fn new(binary: String, player: Arc<Player>, page_size: u32) -> Self {
+        YtDlp {
+            binary,
+            page_size: page_size.max(1),
+            player,
+        }
+    }
+}
+
+// YtDlpEntry is the metadata yt-dlp prints for each search result with --dump-json.
+#[derive(Debug, serde::Deserialize)]
+struct YtDlpEntry {
+    id: String,
+    title: Option<String>,
+    channel: Option<String>,
+    uploader: Option<String>,
+    duration: Option<f64>,
+}
+
+// search runs a yt-dlp search for the first count results for query, skipping the first skip of
+// them. yt-dlp can't start a search part way through, so later pages search for everything up to
+// the end of the page.
+async fn search(
+    binary: &str,
+    query: &str,
+    skip: usize,
+    count: usize,
+) -> anyhow::Result<Vec<AudioResult>> {
+    let output = tokio::process::Command::new(binary)
+        .args([
+            "--dump-json",
+            "--flat-playlist",
+            "--no-warnings",
+            &format!("ytsearch{}:{}", skip + count, query),
+        ])
+        .output()
+        .await
+        .map_err(|e| anyhow!("Failed to execute {}: {}", binary, e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "yt-dlp search failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let results = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<YtDlpEntry>(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                println!("Failed to parse yt-dlp result: {}", e);
+                None
+            }
+        })
+        .skip(skip)
+        .map(|entry| AudioResult {
+            title: entry.title.unwrap_or_else(|| entry.id.clone()),
+            artist: entry.channel.or(entry.uploader).unwrap_or_default(),
+            duration: entry
+                .duration
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .map(Duration::from_secs_f64)
+                .unwrap_or_default(),
+            id: entry.id,
+        })
+        .collect();
+
+    Ok(results)
+}
+
+// stream_url asks yt-dlp for a URL the audio of the video with the given id can be streamed from.
+pub(crate) fn stream_url(binary: &str, id: &str) -> Option<String> {
+    let url = format!("https://www.youtube.com/watch?v={}", id);
+
+    let output = match Command::new(binary)
+        .args([
+            "-f",
+            AUDIO_FORMAT,
+            "--get-url",
+            "--extract-audio",
+            "--audio-format",
+            "mp3",
+            &url,
+        ])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("Failed to execute {}: {}", binary, e);
+            return None;
+        }
+    };
+
+    if output.status.success() {
+        let audio_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Some(audio_url)
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        eprintln!("yt-dlp failed: {}", error);
+
+        // Try a different approach - maybe without audio format specification
+        println!("Retrying with simplified parameters...");
+        let retry_output = Command::new(binary)
+            .args(["-f", AUDIO_FORMAT, "--get-url", &url])
+            .output()
+            .ok()?;
+
+        if retry_output.status.success() {
+            let audio_url = String::from_utf8_lossy(&retry_output.stdout)
+                .trim()
+                .to_string();
+            println!("Retry successful. Got audio URL: {}", audio_url);
+            Some(audio_url)
+        } else {
+            eprintln!(
+                "Retry also failed: {}",
+                String::from_utf8_lossy(&retry_output.stderr)
+            );
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl AudioFetcher for YtDlp {
+    async fn search(&self, query: &str) -> anyhow::Result<Vec<AudioResult>> {
+        Ok(self.search_page(query, None).await?.results)
+    }
+
+    // Pages are tokened by how many results came before them.
+    async fn search_page(
+        &self,
+        query: &str,
+        page_token: Option<&str>,
+    ) -> anyhow::Result<AudioPage> {
+        if query.is_empty() {
+            return Ok(AudioPage::default());
+        }
+
+        let skip = page_token
+            .map(|token| token.parse::<usize>())
+            .transpose()?
+            .unwrap_or_default();
+        let count = self.page_size as usize;
+
+        let results = search(
+            &self.binary,
+            &format!("{} {}", query, SEARCH_SUFFIX),
+            skip,
+            count,
+        )
+        .await?;
+
+        // A short page means the search has run out of results.
+        let next_page = match results.len() < count {
+            true => None,
+            false => Some((skip + count).to_string()),
+        };
+
+        Ok(AudioPage { results, next_page })
+    }
+}
+
+impl AudioService for YtDlp {
+    fn play(&self, id: &str) {
+        let generation = self.player.generation();
+        let url = match stream_url(&self.binary, id) {
+            Some(url) => url,
+            None => {
+                println!("Failed to get url for {}", id);
+                return;
+            }
+        };
+
+        if let Err(e) = self.player.play_url(&url, generation) {
+            println!("Failed to play audio: {}", e);
+        }
+    }
+
+    fn pause(&self) {
+        self.player.pause();
+    }
+
+    fn resume(&self) {
+        self.player.resume();
+    }
+
+    fn stop(&self) {
+        self.player.stop();
+    }
+
+    fn seek(&self, position: Duration) {
+        if let Err(e) = self.player.seek(position) {
+            println!("Failed to seek: {}", e);
+        }
+    }
+
+    fn position(&self) -> Option<Duration> {
+        Some(self.player.position())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    // FakeYtDlp is a script standing in for yt-dlp. Its directory is removed when it's dropped.
+    struct FakeYtDlp {
+        dir: PathBuf,
+    }
+
+    impl FakeYtDlp {
+        fn path(&self) -> String {
+            self.dir.join("yt-dlp").to_string_lossy().to_string()
+        }
+    }
+
+    impl Drop for FakeYtDlp {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    // fake_ytdlp writes a yt-dlp that runs script, whatever it's asked.
+    fn fake_ytdlp(name: &str, script: &str) -> FakeYtDlp {
+        let dir = std::env::temp_dir().join(format!("cliraoke-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let fake = FakeYtDlp { dir };
+        std::fs::write(fake.path(), format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(fake.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        fake
+    }
+
+    // prints is a script that prints stdout.
+    fn prints(stdout: &str) -> String {
+        format!("cat <<'EOF'\n{}\nEOF", stdout)
+    }
+
+    #[tokio::test]
+    async fn reads_title_channel_and_duration() {
+        let fake = fake_ytdlp(
+            "metadata",
+            &prints(concat!(
+                r#"{"id": "abc", "title": "Song (Karaoke Version)", "channel": "Sing King", "duration": 215.5}"#,
+                "\n",
+                r#"{"id": "def", "title": "Song", "uploader": "Someone", "duration": null}"#,
+            )),
+        );
+
+        let results = search(&fake.path(), "song", 0, 5).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "abc");
+        assert_eq!(results[0].title, "Song (Karaoke Version)");
+        assert_eq!(results[0].artist, "Sing King");
+        assert_eq!(results[0].duration, Duration::from_millis(215_500));
+        assert_eq!(results[1].artist, "Someone");
+        assert_eq!(results[1].duration, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn skips_earlier_pages_and_bad_lines() {
+        let fake = fake_ytdlp(
+            "paging",
+            &prints(concat!(
+                r#"{"id": "one", "title": "One"}"#,
+                "\n",
+                "not json\n",
+                r#"{"id": "two", "title": "Two"}"#,
+                "\n",
+                r#"{"id": "three"}"#,
+            )),
+        );
+
+        let results = search(&fake.path(), "song", 1, 2).await.unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["two", "three"]);
+        assert_eq!(results[1].title, "three");
+    }
+
+    #[tokio::test]
+    async fn reports_a_failing_search() {
+        let fake = fake_ytdlp("failing", "echo 'ERROR: no network' >&2\nexit 1");
+
+        let err = search(&fake.path(), "song", 0, 5).await.unwrap_err();
+
+        assert!(err.to_string().contains("no network"));
+    }
+
+    #[tokio::test]
+    async fn reports_a_missing_binary() {
+        let err = search("/nonexistent/yt-dlp", "song", 0, 5).await.unwrap_err();
+
+        assert!(err.to_string().contains("Failed to execute"));
+    }
+}
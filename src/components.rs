@@ -2,8 +2,11 @@ use anyhow::Result;
 use ratatui::{backend::Backend, layout::Rect, Frame};
 
 pub(crate) mod help;
+pub(crate) mod playlist;
 pub(crate) mod queue;
+pub(crate) mod restore;
 pub(crate) mod search;
+pub(crate) mod seek;
 pub(crate) mod timer;
 pub(crate) mod title;
 pub(crate) mod lyrics;
@@ -26,6 +26,34 @@ impl RenderableComponent for Help {
       "to ".into(),
       Span::styled("search ", Style::default().fg(Color::Yellow)),
       "for a song, ".into(),
+      Span::styled("space ", Style::default().fg(Color::Red)),
+      "to ".into(),
+      Span::styled("pause", Style::default().fg(Color::Yellow)),
+      ", ".into(),
+      Span::styled("\u{2190}/\u{2192} ", Style::default().fg(Color::Red)),
+      "to ".into(),
+      Span::styled("seek", Style::default().fg(Color::Yellow)),
+      ", ".into(),
+      Span::styled("g ", Style::default().fg(Color::Red)),
+      "to ".into(),
+      Span::styled("go to a time", Style::default().fg(Color::Yellow)),
+      ", ".into(),
+      Span::styled("n ", Style::default().fg(Color::Red)),
+      "to ".into(),
+      Span::styled("skip", Style::default().fg(Color::Yellow)),
+      ", ".into(),
+      Span::styled("r ", Style::default().fg(Color::Red)),
+      "to ".into(),
+      Span::styled("restart", Style::default().fg(Color::Yellow)),
+      ", ".into(),
+      Span::styled("p ", Style::default().fg(Color::Red)),
+      "to go back to the ".into(),
+      Span::styled("previous song", Style::default().fg(Color::Yellow)),
+      ", ".into(),
+      Span::styled("[/] ", Style::default().fg(Color::Red)),
+      "to ".into(),
+      Span::styled("nudge lyrics earlier/later", Style::default().fg(Color::Yellow)),
+      ", ".into(),
       "and ".into(),
       Span::styled("q ", Style::default().fg(Color::Red)),
       "to ".into(),
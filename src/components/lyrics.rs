@@ -1,7 +1,7 @@
 use crate::app::GlobalState;
 use crate::components::RenderableComponent;
 use crate::lyrics::LyricsService;
-use crate::state::{AMGlobalState, get_state};
+use crate::state::{AMGlobalState, SongState, get_guarded_state};
 use ratatui::backend::Backend;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin};
 use ratatui::widgets::{BorderType, Paragraph, Wrap};
@@ -9,12 +9,13 @@ use ratatui::{
     Frame,
     layout::Rect,
     style::{Color, Style, Stylize},
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Borders},
 };
 use std::sync::{Arc, Mutex};
 use color_eyre::owo_colors::OwoColorize;
 use crate::components::title::Title;
+use crate::models::song::LyricLine;
 use crate::util::{EMDASH, EMOJI_MARTINI};
 
 pub struct Lyrics<LS>
@@ -45,12 +46,20 @@ where
     LS: LyricsService + Send + Sync + 'static,
 {
     fn render<B: Backend>(&self, f: &mut Frame, rect: Rect) -> anyhow::Result<()> {
-        let gs = get_state(&self.global_state);
-        let current_song = gs.current_song;
-        let current_lyrics = gs.current_lyrics.clone();
+        // Render straight from the state rather than a copy of it, which would clone the lyrics of
+        // the current song on every frame.
+        let gs = get_guarded_state(&self.global_state);
+        let lyrics_elapsed_ms = gs.lyrics_elapsed_ms();
 
-        match current_song {
+        match &gs.current_song {
             Some(song) => {
+                // Look up the lines to show from the precise elapsed time on every frame.
+                let current_lyrics = match &song.lyric_map {
+                    Some(map) => self.ls.play(lyrics_elapsed_ms, map)?,
+                    None => Vec::new(),
+                };
+                let unsynced = song.lyric_map.is_none() && !song.plain_lyrics.is_empty();
+
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints([
@@ -61,22 +70,42 @@ where
 
                 let (title, body) = (chunks[0], chunks[1]);
 
+                let status = match gs.song_state {
+                    SongState::Paused => "Paused",
+                    _ => "Now Playing",
+                };
+                let badge = match unsynced {
+                    true => "[unsynced] ",
+                    false => "",
+                };
                 let lyrics_title = Title::new(
-                    format!("Now Playing: {} by {} ", song.title, song.artist).as_str(),
+                    format!("{}: {} by {} {}", status, song.title, song.artist, badge).as_str(),
                 );
                 lyrics_title.render::<B>(f, title)?;
 
+                if unsynced {
+                    let lines = teleprompter_lines(
+                        &song.plain_lyrics,
+                        lyrics_elapsed_ms,
+                        song.duration_ms,
+                        body.height,
+                    );
+                    let p = Paragraph::new(lines).alignment(Alignment::Center);
+                    f.render_widget(p, body);
+
+                    return Ok(());
+                }
+
                 // Lyrics vertically centered.
                 let lines = current_lyrics
                     .iter()
                     .enumerate()
                     .map(|(i, line)| {
-                        let line = line.to_string();
-                        let line = line.replace('\n', " ");
                         if i == 1 {
-                            Line::from(line).style(Style::default().fg(Color::Green))
+                            highlight_line(line, lyrics_elapsed_ms)
                         } else {
-                            Line::from(line).style(Style::default().fg(Color::DarkGray))
+                            Line::from(line.text.replace('\n', " "))
+                                .style(Style::default().fg(Color::DarkGray))
                         }
                     })
                     .collect::<Vec<_>>();
@@ -111,3 +140,223 @@ where
         Ok(())
     }
 }
+
+// teleprompter_lines scrolls plain (unsynced) lyrics evenly over the length of the song, keeping
+// the line we guess is being sung in the middle of a pane of the given height. Blank lines between
+// verses are dropped so they don't take up singing time.
+fn teleprompter_lines(
+    plain: &str,
+    elapsed_ms: u64,
+    duration_ms: u64,
+    height: u16,
+) -> Vec<Line<'static>> {
+    let text: Vec<&str> = plain
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    // Without a duration there's nothing to pace against, so the lyrics stay at the top.
+    let current = match duration_ms {
+        0 => 0,
+        duration_ms => {
+            let progress = elapsed_ms.min(duration_ms) as f64 / duration_ms as f64;
+            ((text.len() as f64 * progress) as usize).min(text.len() - 1)
+        }
+    };
+
+    // Pad the top so the current line sits in the middle of the pane.
+    let middle = (height / 2) as usize;
+    let first = current.saturating_sub(middle);
+    let padding = middle.saturating_sub(current);
+
+    let mut lines = vec![Line::default(); padding];
+    lines.extend(
+        text.iter()
+            .enumerate()
+            .skip(first)
+            .take((height as usize).saturating_sub(padding))
+            .map(|(i, line)| {
+                let color = match i == current {
+                    true => Color::Green,
+                    false => Color::DarkGray,
+                };
+                Line::from(line.to_string()).style(Style::default().fg(color))
+            }),
+    );
+
+    lines
+}
+
+// highlight_line colors the current line. Lines with word timings get a karaoke "color wipe",
+// where each word fills in as it is sung; lines without them are highlighted all at once.
+fn highlight_line(line: &LyricLine, elapsed_ms: u64) -> Line<'static> {
+    let sung = Style::default().fg(Color::Green);
+    let unsung = Style::default().fg(Color::White);
+
+    if line.words.is_empty() {
+        return Line::from(line.text.replace('\n', " ")).style(sung);
+    }
+
+    let mut spans = Vec::new();
+    for (i, word) in line.words.iter().enumerate() {
+        // A word is sung until the next one starts. The last word has no end, so it fills in as
+        // soon as it starts.
+        let end_ms = line.words.get(i + 1).map(|next| next.start_ms);
+
+        match end_ms {
+            _ if elapsed_ms < word.start_ms => {
+                spans.push(Span::styled(word.text.clone(), unsung));
+            }
+            Some(end_ms) if elapsed_ms < end_ms && end_ms > word.start_ms => {
+                let chars: Vec<char> = word.text.chars().collect();
+                let progress = (elapsed_ms - word.start_ms) as f64 / (end_ms - word.start_ms) as f64;
+                let split = ((chars.len() as f64) * progress).round() as usize;
+
+                spans.push(Span::styled(chars[..split].iter().collect::<String>(), sung));
+                spans.push(Span::styled(chars[split..].iter().collect::<String>(), unsung));
+            }
+            _ => {
+                spans.push(Span::styled(word.text.clone(), sung));
+            }
+        }
+    }
+
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::song::LyricWord;
+
+    fn line(words: &[(u64, &str)]) -> LyricLine {
+        LyricLine {
+            text: words.iter().map(|(_, text)| *text).collect(),
+            words: words
+                .iter()
+                .map(|(start_ms, text)| LyricWord {
+                    start_ms: *start_ms,
+                    text: text.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn spans(line: &Line) -> Vec<(String, Option<Color>)> {
+        line.spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style.fg))
+            .collect()
+    }
+
+    fn sung(text: &str) -> (String, Option<Color>) {
+        (text.to_string(), Some(Color::Green))
+    }
+
+    fn unsung(text: &str) -> (String, Option<Color>) {
+        (text.to_string(), Some(Color::White))
+    }
+
+    fn lyrics(count: usize) -> String {
+        (0..count).map(|i| format!("line {}\n", i)).collect()
+    }
+
+    fn rows(lines: &[Line]) -> Vec<(String, Option<Color>)> {
+        lines.iter().map(|line| (line.to_string(), line.style.fg)).collect()
+    }
+
+    fn blank() -> (String, Option<Color>) {
+        (String::new(), None)
+    }
+
+    fn current(text: &str) -> (String, Option<Color>) {
+        sung(text)
+    }
+
+    fn other(text: &str) -> (String, Option<Color>) {
+        (text.to_string(), Some(Color::DarkGray))
+    }
+
+    #[test]
+    fn shows_nothing_without_lyrics() {
+        assert!(teleprompter_lines("", 0, 100_000, 5).is_empty());
+        assert!(teleprompter_lines("\n  \n", 0, 100_000, 5).is_empty());
+    }
+
+    #[test]
+    fn pads_the_first_line_down_to_the_middle() {
+        let lines = teleprompter_lines(&lyrics(10), 0, 100_000, 5);
+
+        assert_eq!(
+            rows(&lines),
+            [blank(), blank(), current("line 0"), other("line 1"), other("line 2")]
+        );
+    }
+
+    #[test]
+    fn keeps_the_current_line_in_the_middle() {
+        let lines = teleprompter_lines(&lyrics(10), 50_000, 100_000, 5);
+
+        assert_eq!(
+            rows(&lines),
+            [other("line 3"), other("line 4"), current("line 5"), other("line 6"), other("line 7")]
+        );
+    }
+
+    #[test]
+    fn stops_on_the_last_line_at_the_end() {
+        let lines = teleprompter_lines(&lyrics(10), 500_000, 100_000, 5);
+
+        assert_eq!(
+            rows(&lines),
+            [other("line 7"), other("line 8"), current("line 9")]
+        );
+    }
+
+    #[test]
+    fn stays_at_the_top_without_a_duration_and_skips_blank_lines() {
+        let lines = teleprompter_lines("one\n\n  two  \n", 60_000, 0, 3);
+
+        assert_eq!(rows(&lines), [blank(), current("one"), other("two")]);
+    }
+
+    #[test]
+    fn highlights_a_line_without_words_all_at_once() {
+        let highlighted = highlight_line(&LyricLine::new("Is this the real life?"), 0);
+
+        assert_eq!(highlighted.style.fg, Some(Color::Green));
+        assert_eq!(highlighted.to_string(), "Is this the real life?");
+    }
+
+    #[test]
+    fn wipes_through_the_word_being_sung() {
+        let line = line(&[(1_000, "Sing "), (2_000, "along"), (3_000, "")]);
+
+        assert_eq!(
+            spans(&highlight_line(&line, 1_400)),
+            [sung("Si"), unsung("ng "), unsung("along"), unsung("")]
+        );
+        assert_eq!(
+            spans(&highlight_line(&line, 2_500)),
+            [sung("Sing "), sung("alo"), unsung("ng"), unsung("")]
+        );
+    }
+
+    #[test]
+    fn leaves_words_unsung_until_they_start() {
+        let line = line(&[(1_000, "Sing "), (2_000, "along")]);
+
+        assert_eq!(
+            spans(&highlight_line(&line, 500)),
+            [unsung("Sing "), unsung("along")]
+        );
+        assert_eq!(
+            spans(&highlight_line(&line, 2_000)),
+            [sung("Sing "), sung("along")]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use super::RenderableComponent;
+use crate::app::GlobalState;
+use crate::audio::AudioResult;
+use crate::events::{EventState, Key};
+use crate::lyrics::{LyricsFetcher, LyricsResult, LyricsSignature};
+use crate::models::pairing::best_pair;
+use crate::models::song::Song;
+use crate::state::{get_state, with_state, Focus, InputMode};
+use crate::storage::playlist::{self, PlaylistEntry};
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+// LOOKUP_TIMEOUT is how long each lyrics lookup of an import gets before it's given up on.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+// MIN_MATCH_SCORE is the least confidence a lyrics search result needs to be paired with an
+// imported song whose lyrics couldn't be found by id.
+const MIN_MATCH_SCORE: f64 = 0.5;
+
+// PlaylistAction is what the playlist prompt does with the path it's given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaylistAction {
+    Import,
+    Export,
+}
+
+// ImportUpdate is sent from an import running in the background as it makes progress.
+enum ImportUpdate {
+    Song(Box<Song>),
+    Done,
+}
+
+// Playlist is the footer prompt for exporting the queue to a playlist file and importing one into
+// the queue. Imports look the lyrics of every song up again, so they run in the background and
+// songs join the queue as they're ready.
+pub struct Playlist<LF>
+where
+    LF: LyricsFetcher + Send + Sync + 'static,
+{
+    global_state: Arc<Mutex<GlobalState>>,
+    lyrics_fetcher: Arc<LF>,
+    action: PlaylistAction,
+    input: Input,
+    // status reports how the last import or export went.
+    status: Option<String>,
+
+    // imported and unmatched count the songs of the import in flight that have been queued so far,
+    // and how many of those are missing lyrics, out of importing.
+    importing: usize,
+    imported: usize,
+    unmatched: usize,
+    task: Option<JoinHandle<()>>,
+    updates_rx: UnboundedReceiver<ImportUpdate>,
+    updates_tx: UnboundedSender<ImportUpdate>,
+}
+
+impl<LF> Playlist<LF>
+where
+    LF: LyricsFetcher + Send + Sync + 'static,
+{
+    pub fn new(state: Arc<Mutex<GlobalState>>, lf: Arc<LF>) -> Self {
+        let (updates_tx, updates_rx) = unbounded_channel();
+
+        Self {
+            global_state: state,
+            lyrics_fetcher: lf,
+            action: PlaylistAction::Import,
+            input: Input::default(),
+            status: None,
+
+            importing: 0,
+            imported: 0,
+            unmatched: 0,
+            task: None,
+            updates_rx,
+            updates_tx,
+        }
+    }
+
+    // open shows the prompt for action.
+    pub fn open(&mut self, action: PlaylistAction) {
+        self.action = action;
+        self.input.reset();
+        self.status = None;
+        with_state(&self.global_state, |s| {
+            s.focus = Focus::Playlist;
+            s.mode = InputMode::Input;
+        });
+    }
+
+    // close hides the prompt, back to the queue it was opened from. An import carries on.
+    fn close(&mut self) {
+        self.input.reset();
+        with_state(&self.global_state, |s| {
+            s.focus = Focus::Queue;
+            s.mode = InputMode::Nav;
+        });
+    }
+
+    // export saves the song that's playing and the queue after it to the playlist at path, so the
+    // setlist picks up where it left off when it's imported again.
+    fn export(&mut self, path: PathBuf) {
+        let state = get_state(&self.global_state);
+        let songs: Vec<Song> = state.current_song.into_iter().chain(state.song_list).collect();
+        if songs.is_empty() {
+            self.status = Some("Nothing is playing or queued to export".to_string());
+            return;
+        }
+
+        self.status = Some(match playlist::export(&path, &songs) {
+            Ok(()) => format!("Exported {} songs to {}", songs.len(), path.display()),
+            Err(e) => e.to_string(),
+        });
+    }
+
+    // import reads the playlist at path and starts queueing its songs in the background.
+    fn import(&mut self, path: PathBuf) {
+        if self.task.as_ref().is_some_and(|task| !task.is_finished()) {
+            self.status = Some("Still importing the last playlist".to_string());
+            return;
+        }
+
+        let entries = match playlist::import(&path) {
+            Ok(entries) if entries.is_empty() => {
+                self.status = Some(format!("{} has no songs in it", path.display()));
+                return;
+            }
+            Ok(entries) => entries,
+            Err(e) => {
+                self.status = Some(e.to_string());
+                return;
+            }
+        };
+
+        self.importing = entries.len();
+        self.imported = 0;
+        self.unmatched = 0;
+        self.status = Some(self.progress());
+
+        let lf = self.lyrics_fetcher.clone();
+        let tx = self.updates_tx.clone();
+        self.task = Some(tokio::spawn(async move {
+            // Songs are imported one at a time, so they're queued in the playlist's order.
+            for entry in entries {
+                let song = import_song(lf.as_ref(), entry).await;
+                let _ = tx.send(ImportUpdate::Song(Box::new(song)));
+            }
+            let _ = tx.send(ImportUpdate::Done);
+        }));
+    }
+
+    fn progress(&self) -> String {
+        format!("Imported {} of {} songs", self.imported, self.importing)
+    }
+
+    // tick queues the songs an import has finished with.
+    pub(crate) fn tick(&mut self) {
+        while let Ok(update) = self.updates_rx.try_recv() {
+            match update {
+                ImportUpdate::Song(song) => {
+                    if song.lyric_map.is_none() && song.plain_lyrics.is_empty() {
+                        self.unmatched += 1;
+                    }
+                    with_state(&self.global_state, |s| s.enqueue(*song));
+
+                    self.imported += 1;
+                    self.status = Some(self.progress());
+                }
+                ImportUpdate::Done => {
+                    let mut status = format!("Imported {} songs", self.imported);
+                    if self.unmatched > 0 {
+                        status.push_str(&format!(", {} without lyrics", self.unmatched));
+                    }
+                    self.status = Some(status);
+                }
+            }
+        }
+    }
+
+    pub async fn event(&mut self, key: Key) -> Result<EventState> {
+        match key {
+            Key::Esc => {
+                self.close();
+            }
+            Key::Enter => {
+                let path = expand_home(self.input.value().trim());
+                if path.as_os_str().is_empty() {
+                    return Ok(EventState::Consumed);
+                }
+
+                match self.action {
+                    PlaylistAction::Export => self.export(path),
+                    PlaylistAction::Import => self.import(path),
+                }
+            }
+            Key::Backspace => {
+                self.input.handle_event(&Event::Key(KeyEvent::new(
+                    KeyCode::Backspace,
+                    KeyModifiers::NONE,
+                )));
+            }
+            Key::Char(v) => {
+                self.input.handle_event(&Event::Key(KeyEvent::new(
+                    KeyCode::Char(v),
+                    KeyModifiers::NONE,
+                )));
+            }
+            _ => return Ok(EventState::NotConsumed),
+        }
+
+        Ok(EventState::Consumed)
+    }
+}
+
+// import_song builds the song for a playlist entry, finding its lyrics by their saved id or, failing
+// that, by its title, artist and duration. Songs whose lyrics can't be found are queued without.
+async fn import_song<LF: LyricsFetcher + Sync>(lf: &LF, entry: PlaylistEntry) -> Song {
+    let audio = AudioResult {
+        id: entry.video_id,
+        title: entry.title,
+        artist: entry.artist,
+        duration: Duration::from_secs(entry.duration_secs),
+    };
+
+    let mut song = Song::new().with_ar(audio.clone());
+    song.title = audio.title.clone();
+    song.artist = audio.artist.clone();
+
+    let Some(lr) = find_lyrics(lf, &audio, &entry.lyric_id).await else {
+        return song;
+    };
+
+    let map = lf
+        .parse(lr.synced_lyrics.to_owned())
+        .await
+        .unwrap_or_else(|e| {
+            println!("Error parsing lyrics: {}", e);
+            None
+        });
+
+    song.with_lr(lr, map)
+}
+
+async fn find_lyrics<LF: LyricsFetcher + Sync>(
+    lf: &LF,
+    audio: &AudioResult,
+    lyric_id: &str,
+) -> Option<LyricsResult> {
+    if !lyric_id.is_empty() {
+        match timeout(LOOKUP_TIMEOUT, lf.get_by_id(lyric_id)).await {
+            Ok(Ok(Some(lr))) => return Some(lr),
+            Ok(Ok(None)) => {}
+            Ok(Err(e)) => {
+                println!("Error looking up lyrics {}: {}", lyric_id, e);
+            }
+            Err(_) => {
+                println!("Error looking up lyrics {}: timed out", lyric_id);
+            }
+        }
+    }
+
+    if audio.title.is_empty() {
+        return None;
+    }
+
+    let signature = LyricsSignature {
+        title: audio.title.clone(),
+        artist: audio.artist.clone(),
+        album: String::new(),
+        duration: audio.duration,
+    };
+    if let Ok(Ok(Some(lr))) = timeout(LOOKUP_TIMEOUT, lf.get(&signature)).await {
+        return Some(lr);
+    }
+
+    let query = format!("{} {}", audio.artist, audio.title);
+    let results = timeout(LOOKUP_TIMEOUT, lf.search(query.trim()))
+        .await
+        .ok()?
+        .ok()?;
+
+    let best = best_pair(std::slice::from_ref(audio), &results)?;
+    if best.score < MIN_MATCH_SCORE {
+        return None;
+    }
+
+    results.into_iter().nth(best.lyrics)
+}
+
+// expand_home expands a leading ~ to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+impl<LF> RenderableComponent for Playlist<LF>
+where
+    LF: LyricsFetcher + Send + Sync + 'static,
+{
+    fn render<B: Backend>(&self, f: &mut Frame, rect: Rect) -> anyhow::Result<()> {
+        let (prompt, action) = match self.action {
+            PlaylistAction::Export => ("Export the song playing and the queue to ", " to save)"),
+            PlaylistAction::Import => ("Import a playlist from ", " to load)"),
+        };
+
+        // Show how the last import or export went until the user starts typing again.
+        let text = match &self.status {
+            Some(status) if self.input.value().is_empty() => {
+                Line::from(status.clone()).fg(Color::DarkGray)
+            }
+            _ => Line::from(self.input.value()),
+        };
+
+        let input = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title_alignment(Alignment::Center)
+                    .title(Line::from(vec![
+                        Span::raw(prompt),
+                        Span::styled(
+                            "(.json, .m3u or .csv; press ",
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            "ENTER",
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .fg(Color::LightRed),
+                        ),
+                        Span::styled(action, Style::default().fg(Color::DarkGray)),
+                    ])),
+            );
+
+        f.render_widget(input, rect);
+
+        Ok(())
+    }
+}
@@ -1,13 +1,14 @@
 use crate::app::GlobalState;
 use crate::components::RenderableComponent;
 use crate::events::{EventState, Key};
+use crate::models::song::Song;
 use crate::state::{get_state, with_state, Focus};
 use crate::util::EMDASH;
 use color_eyre::eyre::Result;
 use ratatui::backend::Backend;
-use ratatui::layout::Alignment;
-use ratatui::style::Color::{Black, Cyan};
-use ratatui::widgets::BorderType;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::style::Color::{Black, Cyan, DarkGray, Yellow};
+use ratatui::widgets::{BorderType, ListState, Paragraph, Wrap};
 use ratatui::{
     layout::Rect,
     style::{Style, Stylize},
@@ -22,15 +23,78 @@ use std::sync::{Arc, Mutex};
 #[derive(Default)]
 pub struct Queue {
     pub global_state: Arc<Mutex<GlobalState>>,
+    list_state: ListState,
+    // details is whether the selected song's details are open.
+    details: bool,
 }
 
 impl Queue {
     pub fn new(state: Arc<Mutex<GlobalState>>) -> Self {
         Self {
             global_state: state,
+            list_state: ListState::default(),
+            details: false,
         }
     }
 
+    // selected is the index of the selected song, kept within the queue as songs leave it.
+    fn selected(&self, len: usize) -> Option<usize> {
+        match len {
+            0 => None,
+            len => Some(self.list_state.selected().unwrap_or(0).min(len - 1)),
+        }
+    }
+
+    // select moves the selection by offset, stopping at either end of the queue.
+    fn select(&mut self, offset: isize) {
+        let len = get_state(&self.global_state).song_list.len();
+        if let Some(index) = self.selected(len) {
+            let index = index.saturating_add_signed(offset).min(len - 1);
+            self.list_state.select(Some(index));
+        }
+    }
+
+    // move_song moves the selected song by offset, keeping it selected. It turns the singer rotation
+    // off, since the order is now the user's.
+    fn move_song(&mut self, offset: isize) {
+        let mut moved = None;
+        with_state(&self.global_state, |s| {
+            let len = s.song_list.len();
+            if let Some(from) = self.selected(len) {
+                let to = from.saturating_add_signed(offset).min(len - 1);
+                let song = s.song_list.remove(from);
+                s.song_list.insert(to, song);
+                moved = Some(to);
+
+                // The rotation would undo the move as soon as another song is queued.
+                s.set_rotation(false);
+            }
+        });
+
+        if moved.is_some() {
+            self.list_state.select(moved);
+        }
+    }
+
+    // move_to_top moves the selected song to the front of the queue, so it plays next.
+    fn move_to_top(&mut self) {
+        let len = get_state(&self.global_state).song_list.len();
+        if let Some(index) = self.selected(len) {
+            self.move_song(-(index as isize));
+        }
+    }
+
+    // remove takes the selected song out of the queue.
+    fn remove(&mut self) {
+        with_state(&self.global_state, |s| {
+            if let Some(index) = self.selected(s.song_list.len()) {
+                s.song_list.remove(index);
+            }
+        });
+
+        self.details = false;
+    }
+
     pub async fn event(&mut self, key: Key) -> Result<EventState> {
         match key {
             Key::Char('/') => {
@@ -40,45 +104,219 @@ impl Queue {
 
                 return Ok(EventState::Consumed)
             }
-            _ => {}
+            Key::Up => self.select(-1),
+            Key::Down => self.select(1),
+            Key::ShiftUp => self.move_song(-1),
+            Key::ShiftDown => self.move_song(1),
+            Key::Char('t') => self.move_to_top(),
+            Key::Char('o') => with_state(&self.global_state, |s| s.set_rotation(!s.rotation)),
+            Key::Char('d') | Key::Delete => self.remove(),
+            Key::Enter => self.details = !self.details,
+            Key::Esc if self.details => self.details = false,
+            _ => return Ok(EventState::NotConsumed),
         }
 
-        Ok(EventState::NotConsumed)
+        Ok(EventState::Consumed)
+    }
+}
+
+// queue_row is how a song is listed in the queue, led by its singer if it has one.
+fn queue_row(song: &Song) -> String {
+    match song.singer.is_empty() {
+        true => format!("{} {} {}", song.title, EMDASH, song.artist),
+        false => format!("{}: {} {} {}", song.singer, song.title, EMDASH, song.artist),
     }
 }
 
+// details_lines describes a queued song in full.
+fn details_lines(song: &Song) -> Vec<Line<'static>> {
+    let duration_s = song.duration_ms / 1000;
+    let lyrics = match (&song.lyric_map, song.plain_lyrics.is_empty()) {
+        (Some(map), _) => format!("synced, {} lines", map.len()),
+        (None, false) => "plain (unsynced)".to_string(),
+        (None, true) => "none".to_string(),
+    };
+
+    vec![
+        Line::from(song.title.clone()).fg(Yellow),
+        Line::from(format!("by {}", song.artist)),
+        Line::from(format!(
+            "Singer: {}",
+            if song.singer.is_empty() { "-" } else { &song.singer }
+        )),
+        Line::from(format!("Length: {}:{:02}", duration_s / 60, duration_s % 60)),
+        Line::from(format!("Lyrics: {}", lyrics)),
+        Line::from(format!("Audio: {}", song.video_id)).fg(DarkGray),
+        Line::from(format!("Lyrics id: {}", song.lyric_id)).fg(DarkGray),
+    ]
+}
+
 impl RenderableComponent for Queue {
     fn render<B: Backend>(
         &self,
         f: &mut Frame,
         rect: Rect,
     ) -> anyhow::Result<()> {
-        let songs = get_state(&self.global_state).song_list.clone();
+        let state = get_state(&self.global_state);
+        let songs = &state.song_list;
+        let selected = self.selected(songs.len());
+
+        let (rect, details_rect) = match (self.details, selected) {
+            (true, Some(_)) => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(3), Constraint::Length(9)])
+                    .split(rect);
+                (chunks[0], Some(chunks[1]))
+            }
+            _ => (rect, None),
+        };
+
         let block = Block::new()
             .title(Line::from(format!(
-                " {} songs in the queue ",
+                " {} songs in the queue{} ",
                 songs.len(),
+                if state.rotation { ", singers take turns" } else { "" },
             )))
             .title_alignment(Alignment::Center)
+            .title_bottom(
+                Line::from(" \u{2191}/\u{2193} select, shift moves, t top, d remove, o rotation, i import, x export, enter details ")
+                    .fg(DarkGray)
+                    .centered(),
+            )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded);
 
         // Iterate through all elements in the `items` and stylize them.
         let items: Vec<ListItem> = songs
             .iter()
-            .enumerate()
-            .map(|(i, song)| ListItem::new(format!("{} {} {}", song.title, EMDASH, song.artist)).bg(Black))
+            .map(|song| ListItem::new(queue_row(song)).bg(Black))
             .collect();
 
+        // Lead the queue with who's up next.
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+        let (up_next, rect) = (chunks[0], chunks[1]);
+
+        if let Some(next) = state.up_next() {
+            f.render_widget(Paragraph::new(next).fg(Yellow).centered(), up_next);
+        }
+
         // Create a List from all list items and highlight the currently selected one
         let list = List::new(items)
-            .block(block)
             .highlight_style(Style::new().bg(Cyan).fg(Black))
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::Always);
 
-        f.render_widget(list, rect); // should be stateful
+        let mut list_state = self.list_state.clone();
+        list_state.select(selected);
+        f.render_stateful_widget(list, rect, &mut list_state);
+
+        if let (Some(details_rect), Some(song)) = (details_rect, selected.map(|i| &songs[i])) {
+            let details = Paragraph::new(details_lines(song))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::new()
+                        .title(" Details ")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded),
+                );
+            f.render_widget(details, details_rect);
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // queue opens a queue of songs with the given titles, with the song at selected selected.
+    fn queue(titles: &[&str], selected: usize) -> Queue {
+        let mut state = GlobalState::new();
+        state.song_list = titles
+            .iter()
+            .map(|title| {
+                let mut song = Song::new();
+                song.title = title.to_string();
+                song
+            })
+            .collect();
+
+        let mut queue = Queue::new(Arc::new(Mutex::new(state)));
+        queue.list_state.select(Some(selected));
+        queue
+    }
+
+    fn titles(queue: &Queue) -> Vec<String> {
+        get_state(&queue.global_state)
+            .song_list
+            .iter()
+            .map(|song| song.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn moves_a_song_and_keeps_it_selected() {
+        let mut queue = queue(&["a", "b", "c"], 1);
+
+        queue.move_song(1);
+
+        assert_eq!(titles(&queue), ["a", "c", "b"]);
+        assert_eq!(queue.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn stops_moving_at_either_end() {
+        let mut queue = queue(&["a", "b", "c"], 2);
+        queue.move_song(1);
+        assert_eq!(titles(&queue), ["a", "b", "c"]);
+        assert_eq!(queue.list_state.selected(), Some(2));
+
+        let mut queue = self::queue(&["a", "b", "c"], 0);
+        queue.move_song(-1);
+        assert_eq!(titles(&queue), ["a", "b", "c"]);
+        assert_eq!(queue.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn moves_a_song_to_the_top() {
+        let mut queue = queue(&["a", "b", "c"], 2);
+
+        queue.move_to_top();
+
+        assert_eq!(titles(&queue), ["c", "a", "b"]);
+        assert_eq!(queue.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn moving_a_song_turns_the_rotation_off() {
+        let mut queue = queue(&["a", "b"], 0);
+        with_state(&queue.global_state, |s| s.rotation = true);
+
+        queue.move_song(1);
+
+        assert!(!get_state(&queue.global_state).rotation);
+    }
+
+    #[test]
+    fn removing_the_last_song_selects_the_new_last() {
+        let mut queue = queue(&["a", "b", "c"], 2);
+
+        queue.remove();
+
+        assert_eq!(titles(&queue), ["a", "b"]);
+        assert_eq!(queue.selected(2), Some(1));
+
+        queue.remove();
+        queue.remove();
+        assert!(titles(&queue).is_empty());
+        assert_eq!(queue.selected(0), None);
+        queue.remove();
+    }
+}
@@ -0,0 +1,110 @@
+use super::RenderableComponent;
+use crate::app::GlobalState;
+use crate::events::{EventState, Key};
+use crate::state::{with_state, Focus};
+use crate::storage::session::Session;
+use crate::util::EMDASH;
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use std::sync::{Arc, Mutex};
+
+// Restore offers, in the footer, to pick up the queue where the last run left off.
+pub struct Restore {
+    global_state: Arc<Mutex<GlobalState>>,
+    // session is the saved session while the offer is open.
+    session: Option<Session>,
+    accepted: Option<Session>,
+}
+
+impl Restore {
+    pub fn new(state: Arc<Mutex<GlobalState>>, session: Option<Session>) -> Self {
+        Self {
+            global_state: state,
+            session,
+            accepted: None,
+        }
+    }
+
+    // pending is whether the offer hasn't been answered yet.
+    pub fn pending(&self) -> bool {
+        self.session.is_some()
+    }
+
+    // take_accepted returns the saved session, if the user chose to restore it.
+    pub fn take_accepted(&mut self) -> Option<Session> {
+        self.accepted.take()
+    }
+
+    fn close(&mut self) {
+        self.session = None;
+        with_state(&self.global_state, |s| {
+            s.focus = Focus::Home;
+        });
+    }
+
+    pub async fn event(&mut self, key: Key) -> Result<EventState> {
+        match key {
+            Key::Char('y') | Key::Enter => {
+                self.accepted = self.session.take();
+                self.close();
+            }
+            Key::Char('n') | Key::Esc => {
+                self.close();
+            }
+            _ => return Ok(EventState::NotConsumed),
+        }
+
+        Ok(EventState::Consumed)
+    }
+}
+
+impl RenderableComponent for Restore {
+    fn render<B: Backend>(&self, f: &mut Frame, rect: Rect) -> anyhow::Result<()> {
+        let Some(session) = &self.session else {
+            return Ok(());
+        };
+
+        let mut summary = format!("{} songs in the queue", session.song_list.len());
+        if let Some(song) = &session.current_song {
+            let elapsed_s = session.current_song_elapsed_ms / 1000;
+            summary.push_str(&format!(
+                " {} {} at {}:{:02}",
+                EMDASH,
+                song.title,
+                elapsed_s / 60,
+                elapsed_s % 60,
+            ));
+        }
+
+        let prompt = Paragraph::new(summary)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title_alignment(Alignment::Center)
+                    .title(Line::from(vec![
+                        Span::raw("Pick up where you left off? "),
+                        Span::styled("(Press ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(
+                            "y",
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .fg(Color::LightRed),
+                        ),
+                        Span::styled(" to restore, ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(
+                            "n",
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .fg(Color::LightRed),
+                        ),
+                        Span::styled(" to start fresh)", Style::default().fg(Color::DarkGray)),
+                    ])),
+            );
+
+        f.render_widget(prompt, rect);
+
+        Ok(())
+    }
+}
@@ -1,29 +1,69 @@
 use super::{Frame, RenderableComponent, ResettableComponent};
 use crate::app::GlobalState;
-use crate::audio::{AudioFetcher, AudioResult};
+use crate::audio::{AudioFetcher, AudioPage, AudioResult};
 use crate::components::search::NavDir::{Down, Up};
 use crate::components::stateful_list::{get_list_items, StatefulList};
 use crate::events::{EventState, Key};
-use crate::lyrics::{LyricsFetcher, LyricsResult};
+use crate::lyrics::lrc;
+use crate::lyrics::{
+    insert_exact_match, rank_by_duration, LyricsFetcher, LyricsResult, LyricsSignature,
+};
+use crate::models::pairing::{best_pair, score, Pairing};
 use crate::models::song::Song;
 use crate::state::{with_state, Focus, InputMode};
+use crate::util::EMDASH;
 use color_eyre::eyre::Result;
 use color_eyre::owo_colors::OwoColorize;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
 };
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 
+// PREVIEW_LINES is how many lines of the highlighted lyrics the preview pane shows.
+const PREVIEW_LINES: usize = 8;
+// LYRICS_ROWS is how many lyrics results the list shows. Only these are paired with the audio, so
+// the best match is always one the user can see.
+const LYRICS_ROWS: usize = 5;
+// SEARCH_TIMEOUT is how long each request of a search may take before we give up on it.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+// LIVE_SEARCH_DEBOUNCE is how long typing has to pause before a live search fires.
+const LIVE_SEARCH_DEBOUNCE: Duration = Duration::from_millis(400);
+// LIVE_SEARCH_MIN_CHARS is the shortest query a live search fires for.
+const LIVE_SEARCH_MIN_CHARS: usize = 3;
+// LIVE_AUDIO_INTERVAL is the least time between live audio searches. Each YouTube search costs
+// quota, so while the user keeps typing the audio results update less often than the lyrics.
+const LIVE_AUDIO_INTERVAL: Duration = Duration::from_secs(2);
+// SPINNER is drawn in the title of a list while its results are loading, a frame per tick.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// SearchUpdate is the outcome of one of the background requests of a search.
+enum SearchUpdate {
+    Audio(anyhow::Result<AudioPage>),
+    MoreAudio(anyhow::Result<AudioPage>),
+    Lyrics(anyhow::Result<Vec<LyricsResult>>),
+    // Exact is the exact lyrics match looked up for the audio with video_id.
+    Exact {
+        video_id: String,
+        result: anyhow::Result<Option<LyricsResult>>,
+    },
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 enum SearchFocus {
     #[default]
     Input,
     Audio,
     Lyrics,
+    // Singer asks who's singing the chosen song before it's queued.
+    Singer,
 }
 
 #[derive(Debug, Clone)]
@@ -31,14 +71,25 @@ struct State<'a> {
     audio_presentation_list: StatefulList<'a>,
     audio_results: Vec<AudioResult>,
     audio_state: ListState,
+    // audio_next_page is the token for the next page of audio results, if there are more.
+    audio_next_page: Option<String>,
 
     lyrics_presentation_list: StatefulList<'a>,
     lyric_results: Vec<LyricsResult>,
     lyrics_state: ListState,
 
+    // audio_loading and lyrics_loading are set while a search's requests are in flight, and
+    // matching while the exact lyrics match for the chosen audio is being looked up.
+    audio_loading: bool,
+    lyrics_loading: bool,
+    matching: bool,
+
+    // pairing is the suggested (audio, lyrics) pair for the current results.
+    pairing: Option<Pairing>,
     song: Song,
     focus: SearchFocus,
     query: Input,
+    singer: Input,
 }
 
 enum NavDir {
@@ -52,14 +103,21 @@ impl State<'_> {
             audio_presentation_list: StatefulList::default(),
             audio_results: vec![],
             audio_state: ListState::default(),
+            audio_next_page: None,
 
             lyrics_presentation_list: StatefulList::default(),
             lyric_results: vec![],
             lyrics_state: ListState::default(),
 
+            audio_loading: false,
+            lyrics_loading: false,
+            matching: false,
+
+            pairing: None,
             song: Song::new(),
             focus: SearchFocus::Input,
             query: Input::default(),
+            singer: Input::default(),
         }
     }
 
@@ -71,39 +129,90 @@ impl State<'_> {
         self.audio_presentation_list = StatefulList::default();
         self.audio_results = vec![];
         self.audio_state = ListState::default();
+        self.audio_next_page = None;
         self.lyrics_presentation_list = StatefulList::default();
         self.lyric_results = vec![];
         self.lyrics_state = ListState::default();
+        self.audio_loading = false;
+        self.lyrics_loading = false;
+        self.matching = false;
+        self.pairing = None;
         self.song = Song::new();
         self.focus = SearchFocus::Input;
         self.query = Input::default();
+        self.singer = Input::default();
     }
 
-    fn with_audio_results(&mut self, res: Vec<AudioResult>) {
-        self.audio_results = res.clone();
-        self.audio_presentation_list = StatefulList::with_items(
-            res.into_iter()
-                .map(|r| {
-                    let item = ListItem::new(r.title);
-                    item
-                })
-                .collect(),
-            None,
-        );
+    fn with_audio_results(&mut self, page: AudioPage) {
+        self.audio_results = page.results;
+        self.audio_next_page = page.next_page;
+        self.refresh_lists();
+    }
+
+    fn with_more_audio_results(&mut self, page: AudioPage) {
+        self.audio_results.extend(page.results);
+        self.audio_next_page = page.next_page;
+        self.refresh_lists();
     }
 
     fn with_lyrics_results(&mut self, res: Vec<LyricsResult>) {
-        self.lyric_results = res.clone();
-        self.lyrics_presentation_list = StatefulList::with_items(
-            res.into_iter()
-                .take(5)
-                .map(|r| {
-                    let item = ListItem::new(format!("{} by {}", r.title, r.artist));
-                    item
-                })
-                .collect(),
-            None,
-        );
+        self.lyric_results = res;
+        self.refresh_lists();
+    }
+
+    // with_exact_match moves the exact lyrics match for the chosen audio to the top of the list,
+    // behind any local files. If the user has already picked another result, it stays picked.
+    fn with_exact_match(&mut self, exact: LyricsResult) {
+        let picked = self
+            .lyrics_state
+            .selected()
+            .filter(|index| *index > 0)
+            .and_then(|index| self.lyric_results.get(index))
+            .map(|lr| lr.id.clone());
+
+        let mut results = std::mem::take(&mut self.lyric_results);
+        insert_exact_match(&mut results, exact);
+        self.with_lyrics_results(results);
+
+        let picked = picked.and_then(|id| self.lyric_results.iter().position(|r| r.id == id));
+        if let Some(index) = picked {
+            self.lyrics_state.select(Some(index));
+        }
+    }
+
+    // refresh_lists suggests the best pair for the current results and rebuilds both lists, badged
+    // with each result's confidence: its best score against the other list, or against the chosen
+    // audio once there is one.
+    fn refresh_lists(&mut self) {
+        self.pairing = best_pair(&self.audio_results, self.visible_lyrics());
+
+        let audio_items = self
+            .audio_results
+            .iter()
+            .map(|ar| {
+                let confidence = self.lyric_results.iter().map(|lr| score(ar, lr)).reduce(f64::max);
+                ListItem::new(audio_row(confidence, ar))
+            })
+            .collect();
+        self.audio_presentation_list = StatefulList::with_items(audio_items, None);
+
+        let chosen = self
+            .audio_results
+            .iter()
+            .find(|ar| ar.id == self.song.video_id);
+        let lyrics_items = self
+            .lyric_results
+            .iter()
+            .take(LYRICS_ROWS)
+            .map(|lr| {
+                let confidence = match chosen {
+                    Some(ar) => Some(score(ar, lr)),
+                    None => self.audio_results.iter().map(|ar| score(ar, lr)).reduce(f64::max),
+                };
+                ListItem::new(lyrics_row(confidence, lr))
+            })
+            .collect();
+        self.lyrics_presentation_list = StatefulList::with_items(lyrics_items, None);
     }
 
     fn with_state<F, R>(&mut self, f: F) -> R
@@ -122,21 +231,48 @@ impl State<'_> {
 
     // TODO: There must be a way to do this with generics.
     fn navigate(&mut self, focus: SearchFocus, dir: NavDir) {
-        let state = match focus {
-            SearchFocus::Audio => &mut self.audio_state,
-            SearchFocus::Lyrics => &mut self.lyrics_state,
+        // The selection stays on the rows the list shows.
+        let (state, len) = match focus {
+            SearchFocus::Audio => (&mut self.audio_state, self.audio_results.len()),
+            SearchFocus::Lyrics => (
+                &mut self.lyrics_state,
+                self.lyric_results.len().min(LYRICS_ROWS),
+            ),
             _ => return,
         };
 
-        if state.selected().is_some() {
-            let current = state.selected().unwrap_or(0);
+        if let Some(current) = state.selected() {
             let new_index = match dir {
                 NavDir::Up => current.saturating_sub(1),
-                NavDir::Down => current.saturating_add(1),
+                NavDir::Down => current.saturating_add(1).min(len.saturating_sub(1)),
             };
             state.select(Some(new_index));
         }
     }
+
+    // visible_lyrics is the lyrics results the list shows.
+    fn visible_lyrics(&self) -> &[LyricsResult] {
+        &self.lyric_results[..self.lyric_results.len().min(LYRICS_ROWS)]
+    }
+
+    // selected_lyrics is the highlighted lyrics result, if the selection is on a row the list shows.
+    fn selected_lyrics(&self) -> Option<&LyricsResult> {
+        self.lyrics_state
+            .selected()
+            .and_then(|index| self.visible_lyrics().get(index))
+    }
+
+    // at_audio_end is whether the last audio result is selected.
+    fn at_audio_end(&self) -> bool {
+        self.audio_state
+            .selected()
+            .is_some_and(|index| index + 1 >= self.audio_results.len())
+    }
+
+    // can_load_more_audio is whether there's a next page of audio results that isn't loading yet.
+    fn can_load_more_audio(&self) -> bool {
+        self.audio_next_page.is_some() && !self.audio_loading
+    }
 }
 
 pub struct Search<'a, AF, LF>
@@ -148,6 +284,23 @@ where
     global_state: Arc<Mutex<GlobalState>>,
     lyrics_fetcher: Arc<LF>,
     state: State<'a>,
+
+    // generation identifies the latest search, so updates from cancelled ones can be dropped.
+    generation: u64,
+    spinner_frame: usize,
+
+    // live_search searches as the user types, once they pause. edited_at is when the query last
+    // changed, audio_query and lyrics_query are what the lists were last searched for, and
+    // audio_searched_at is when audio was last searched.
+    live_search: bool,
+    edited_at: Option<Instant>,
+    audio_query: String,
+    lyrics_query: String,
+    audio_searched_at: Option<Instant>,
+
+    tasks: Vec<JoinHandle<()>>,
+    updates_rx: UnboundedReceiver<(u64, SearchUpdate)>,
+    updates_tx: UnboundedSender<(u64, SearchUpdate)>,
 }
 
 impl<AF, LF> Search<'_, AF, LF>
@@ -155,33 +308,368 @@ where
     AF: AudioFetcher + Send + Sync + 'static,
     LF: LyricsFetcher + Send + Sync + 'static,
 {
-    pub fn new(global_state: Arc<Mutex<GlobalState>>, af: Arc<AF>, lf: Arc<LF>) -> Self {
+    pub fn new(
+        global_state: Arc<Mutex<GlobalState>>,
+        af: Arc<AF>,
+        lf: Arc<LF>,
+        live_search: bool,
+    ) -> Self {
+        let (updates_tx, updates_rx) = unbounded_channel();
+
         Self {
             audio_fetcher: af,
             global_state,
             lyrics_fetcher: lf,
             state: State::new(),
+
+            generation: 0,
+            spinner_frame: 0,
+
+            live_search,
+            edited_at: None,
+            audio_query: String::new(),
+            lyrics_query: String::new(),
+            audio_searched_at: None,
+
+            tasks: Vec::new(),
+            updates_rx,
+            updates_tx,
+        }
+    }
+
+    // search starts searching audio and lyrics for the query in the background, cancelling any
+    // search still in flight. Results show up in the lists as tick receives them. A list that
+    // already has, or is getting, the results for the query isn't searched again, so pressing Enter
+    // after a live search doesn't spend more YouTube quota on the same results.
+    fn search(&mut self) {
+        let query = self.state.query().to_string();
+        if self.has_audio_for(&query) && self.has_lyrics_for(&query) {
+            return;
+        }
+
+        // cancel forgets the query of any search it cuts short, so that list is searched again.
+        self.cancel();
+
+        if !self.has_audio_for(&query) {
+            self.search_audio(query.clone());
+        }
+        if !self.has_lyrics_for(&query) {
+            self.search_lyrics(query);
+        }
+    }
+
+    fn has_audio_for(&self, query: &str) -> bool {
+        self.audio_query == query
+            && (self.state.audio_loading || !self.state.audio_results.is_empty())
+    }
+
+    fn has_lyrics_for(&self, query: &str) -> bool {
+        self.lyrics_query == query
+            && (self.state.lyrics_loading || !self.state.lyric_results.is_empty())
+    }
+
+    fn search_audio(&mut self, query: String) {
+        let af = self.audio_fetcher.clone();
+        let tx = self.updates_tx.clone();
+        let generation = self.generation;
+        self.tasks.push(tokio::spawn(async move {
+            let page = timeout(SEARCH_TIMEOUT, af.search_page(&query, None))
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+            let _ = tx.send((generation, SearchUpdate::Audio(page)));
+        }));
+
+        self.state.audio_loading = true;
+        self.audio_query = self.state.query().to_string();
+        self.audio_searched_at = Some(Instant::now());
+    }
+
+    // load_more_audio fetches the next page of audio results, if there is one, for the query the
+    // list was searched for.
+    fn load_more_audio(&mut self) {
+        if self.state.audio_loading {
+            return;
+        }
+        let Some(page_token) = self.state.audio_next_page.clone() else {
+            return;
+        };
+
+        let af = self.audio_fetcher.clone();
+        let tx = self.updates_tx.clone();
+        let generation = self.generation;
+        let query = self.audio_query.clone();
+        self.tasks.push(tokio::spawn(async move {
+            let page = timeout(SEARCH_TIMEOUT, af.search_page(&query, Some(&page_token)))
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+            let _ = tx.send((generation, SearchUpdate::MoreAudio(page)));
+        }));
+
+        self.state.audio_loading = true;
+    }
+
+    fn search_lyrics(&mut self, query: String) {
+        let lf = self.lyrics_fetcher.clone();
+        let tx = self.updates_tx.clone();
+        let generation = self.generation;
+        self.tasks.push(tokio::spawn(async move {
+            let results = timeout(SEARCH_TIMEOUT, lf.search(&query))
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+            let _ = tx.send((generation, SearchUpdate::Lyrics(results)));
+        }));
+
+        self.state.lyrics_loading = true;
+        self.lyrics_query = self.state.query().to_string();
+    }
+
+    // cancel aborts the search in flight, if any.
+    fn cancel(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        self.generation += 1;
+
+        // Requests that were cancelled never filled their list, so search for the query again.
+        if self.state.audio_loading {
+            self.audio_query.clear();
         }
+        if self.state.lyrics_loading {
+            self.lyrics_query.clear();
+        }
+
+        self.state.with_state(|s| {
+            s.audio_loading = false;
+            s.lyrics_loading = false;
+            s.matching = false;
+        });
     }
 
-    async fn search(&mut self) {
-        // Search audio.
-        let audio_results = self.audio_fetcher.search(self.state.query()).await;
-        match audio_results {
-            Ok(results) => self.state.with_audio_results(results),
-            Err(e) => {
-                println!("Error searching audio: {}", e);
+    // edited cancels the search for the old query and, with live search on, schedules one for the
+    // new query.
+    fn edited(&mut self) {
+        self.cancel();
+
+        if self.live_search {
+            self.edited_at = Some(Instant::now());
+        }
+    }
+
+    // search_live fires the debounced search for what the user has typed so far. Short queries are
+    // skipped, and audio is searched at most every LIVE_AUDIO_INTERVAL to spare the YouTube quota.
+    fn search_live(&mut self) {
+        let Some(edited_at) = self.edited_at else {
+            return;
+        };
+        if edited_at.elapsed() < LIVE_SEARCH_DEBOUNCE {
+            return;
+        }
+
+        let query = self.state.query().to_string();
+        if query.trim().chars().count() < LIVE_SEARCH_MIN_CHARS {
+            self.edited_at = None;
+            return;
+        }
+
+        if self.lyrics_query != query {
+            self.search_lyrics(query.clone());
+        }
+
+        if self.audio_query != query {
+            let audio_due = self
+                .audio_searched_at
+                .is_none_or(|searched_at| searched_at.elapsed() >= LIVE_AUDIO_INTERVAL);
+            if !audio_due {
+                // Try again on a later tick.
+                return;
             }
+
+            self.search_audio(query);
+        }
+
+        self.edited_at = None;
+    }
+
+    // tick adds the results of finished search requests to the lists and animates the spinner.
+    pub(crate) fn tick(&mut self) {
+        self.spinner_frame = self.spinner_frame.wrapping_add(1);
+
+        if self.live_search && self.state.focus == SearchFocus::Input {
+            self.search_live();
         }
 
-        // Search lyrics.
-        let lyric_results = self.lyrics_fetcher.search(self.state.query()).await;
-        match lyric_results {
-            Ok(results) => self.state.with_lyrics_results(results),
-            Err(e) => {
-                println!("Error searching lyrics: {}", e);
+        while let Ok((generation, update)) = self.updates_rx.try_recv() {
+            if generation != self.generation {
+                continue;
+            }
+
+            match update {
+                SearchUpdate::Audio(page) => {
+                    self.state.audio_loading = false;
+                    match page {
+                        Ok(page) => self.state.with_audio_results(page),
+                        Err(e) => {
+                            println!("Error searching audio: {}", e);
+                        }
+                    }
+                }
+                SearchUpdate::MoreAudio(page) => {
+                    self.state.audio_loading = false;
+                    match page {
+                        Ok(page) => self.state.with_more_audio_results(page),
+                        Err(e) => {
+                            println!("Error loading more audio: {}", e);
+                        }
+                    }
+                }
+                SearchUpdate::Lyrics(results) => {
+                    self.state.lyrics_loading = false;
+                    match results {
+                        Ok(mut results) => {
+                            // If audio was chosen while the lyrics were loading, rank them
+                            // against it.
+                            let chosen = self
+                                .state
+                                .audio_results
+                                .iter()
+                                .find(|ar| ar.id == self.state.song.video_id);
+                            if let Some(ar) = chosen {
+                                rank_by_duration(&mut results, ar.duration);
+                            }
+
+                            self.state.with_lyrics_results(results);
+                        }
+                        Err(e) => {
+                            println!("Error searching lyrics: {}", e);
+                        }
+                    }
+                }
+                SearchUpdate::Exact { video_id, result } => {
+                    // Drop the match for audio the user has since moved on from.
+                    if video_id != self.state.song.video_id {
+                        continue;
+                    }
+
+                    self.state.matching = false;
+                    match result {
+                        Ok(Some(exact)) => self.state.with_exact_match(exact),
+                        Ok(None) => {}
+                        Err(e) => {
+                            println!("Error looking up lyrics: {}", e);
+                        }
+                    }
+                }
             }
         }
+
+        self.tasks.retain(|task| !task.is_finished());
+    }
+
+    // reset cancels any search in flight and clears the component.
+    fn reset(&mut self) {
+        self.cancel();
+        self.state.reset();
+
+        self.edited_at = None;
+        self.audio_query.clear();
+        self.lyrics_query.clear();
+    }
+
+    // match_lyrics reorders the lyrics results to fit the chosen audio, local files first and then
+    // by how close their duration is, and looks up the exact LRCLIB match for the audio's duration
+    // in the background. The exact match goes to the top, behind local files, once tick gets it.
+    fn match_lyrics(&mut self, audio: &AudioResult) {
+        let mut results = self.state.lyric_results.clone();
+        rank_by_duration(&mut results, audio.duration);
+
+        // The closest search result has the best track, artist and album names to look up, since
+        // audio titles (e.g. YouTube's) are rarely clean.
+        let signature = match results.first() {
+            Some(closest) => LyricsSignature {
+                title: closest.title.clone(),
+                artist: closest.artist.clone(),
+                album: closest.album.clone(),
+                duration: audio.duration,
+            },
+            None => LyricsSignature {
+                title: audio.title.clone(),
+                artist: audio.artist.clone(),
+                album: String::new(),
+                duration: audio.duration,
+            },
+        };
+
+        self.state.with_lyrics_results(results);
+
+        let lf = self.lyrics_fetcher.clone();
+        let tx = self.updates_tx.clone();
+        let generation = self.generation;
+        let video_id = audio.id.clone();
+        self.tasks.push(tokio::spawn(async move {
+            let result = timeout(SEARCH_TIMEOUT, lf.get(&signature))
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+            let _ = tx.send((generation, SearchUpdate::Exact { video_id, result }));
+        }));
+
+        self.state.matching = true;
+    }
+
+    // queue_song pairs the chosen audio with the chosen lyrics, if any, then asks who's singing
+    // the song before adding it to the queue.
+    async fn queue_song(&mut self, lr: Option<LyricsResult>) {
+        if let Some(lr) = lr {
+            let map = self
+                .lyrics_fetcher
+                .parse(lr.synced_lyrics.to_owned())
+                .await
+                .unwrap_or_else(|e| {
+                    println!("Error parsing lyrics: {}", e);
+                    // Return an empty result instead of panicking
+                    None
+                });
+            self.state.song = self.state.song.with_lr(lr, map);
+        }
+
+        with_state(&self.global_state, |s| {
+            s.mode = InputMode::Input;
+        });
+        self.state.with_state(|s| {
+            s.focus = SearchFocus::Singer;
+        });
+    }
+
+    // add_to_queue queues the chosen song for the singer that was entered.
+    fn add_to_queue(&mut self) {
+        self.state.song.singer = self.state.singer.value().trim().to_string();
+
+        with_state(&self.global_state, |s| {
+            s.enqueue(self.state.song.clone());
+            s.mode = InputMode::Nav;
+
+            // Return to Home if we have more than one song or Queue to show that the
+            // next song was added to the queue.
+            match s.song_list.len() > 1 {
+                true => s.focus = Focus::Queue,
+                false => s.focus = Focus::Home,
+            }
+        });
+
+        // Clear component state.
+        self.reset();
+    }
+
+    // loading appends the spinner and what's loading to a list title.
+    fn loading<'l>(&self, title: Line<'l>, what: &str) -> Line<'l> {
+        let frame = SPINNER[self.spinner_frame % SPINNER.len()];
+
+        let mut spans = title.spans;
+        spans.push(Span::styled(
+            format!(" {} {}", frame, what),
+            Style::default().fg(Color::DarkGray),
+        ));
+
+        Line::from(spans)
     }
 
     pub async fn event(&mut self, key: Key) -> Result<EventState> {
@@ -198,6 +686,7 @@ where
                     SearchFocus::Lyrics => self.state.with_state(|s| {
                         s.focus = SearchFocus::Input;
                     }),
+                    SearchFocus::Singer => {}
                 }
             }
             (SearchFocus::Audio | SearchFocus::Lyrics, Key::Char('/')) => {
@@ -211,12 +700,13 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Audio | SearchFocus::Lyrics, Key::Esc) => {
-                self.state.reset();
+                self.reset();
 
                 return Ok(EventState::Consumed);
             }
             // Input bindings.
             (SearchFocus::Input, Key::Char('/')) => {
+                self.edited();
                 self.state.with_state(|s| {
                     s.query.reset();
                 });
@@ -227,6 +717,7 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Input, Key::Esc) => {
+                self.edited();
                 if self.state.query.value().is_empty() {
                     self.state.with_state(|s| {
                         s.audio_presentation_list.reset();
@@ -246,6 +737,8 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Input, Key::Backspace) => {
+                // Typing a new query cancels the search for the old one.
+                self.edited();
                 self.state.with_state(|s| {
                     s.query.handle_event(&Event::Key(KeyEvent::new(
                         KeyCode::Backspace,
@@ -256,6 +749,7 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Input, Key::Char(v)) => {
+                self.edited();
                 self.state.with_state(|s| {
                     s.query.handle_event(&Event::Key(KeyEvent::new(
                         KeyCode::Char(v),
@@ -266,7 +760,8 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Input, Key::Enter) => {
-                self.search().await;
+                self.edited_at = None;
+                self.search();
                 with_state(&self.global_state, |s| {
                     s.mode = InputMode::Nav;
                 });
@@ -288,18 +783,34 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Audio, Key::Down) => {
+                // Scrolling past the end of the list loads the next page, if there is one.
+                if self.state.at_audio_end() && self.state.can_load_more_audio() {
+                    self.load_more_audio();
+                    return Ok(EventState::Consumed);
+                }
+
                 self.state.navigate(SearchFocus::Audio, Down);
 
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Audio, Key::Enter) => {
-                self.state.with_state(|s| {
-                    if let Some(index) = s.audio_state.selected() {
-                        s.song = s.song.with_ar(s.audio_results[index].clone());
-                    }
+                let selected = self
+                    .state
+                    .audio_state
+                    .selected()
+                    .and_then(|index| self.state.audio_results.get(index).cloned());
+
+                let matched = selected.is_some();
+                if let Some(ar) = selected {
+                    self.state.song = self.state.song.with_ar(ar.clone());
+                    self.match_lyrics(&ar);
+                }
 
+                self.state.with_state(|s| {
                     s.focus = SearchFocus::Lyrics;
-                    if s.lyrics_state.selected().is_none() {
+
+                    // The best match for the audio is now at the top.
+                    if matched || s.lyrics_state.selected().is_none() {
                         s.lyrics_state.select(Some(0));
                     }
                 });
@@ -318,35 +829,64 @@ where
                 return Ok(EventState::Consumed);
             }
             (SearchFocus::Lyrics, Key::Enter) => {
-                if let Some(index) = self.state.lyrics_state.selected() {
-                    let this_lr = self.state.lyric_results[index].clone();
-                    self.state.song = self.state.song.with_lr(
-                        this_lr.clone(),
-                        self.lyrics_fetcher
-                            .parse(this_lr.synced_lyrics.to_owned())
-                            .await
-                            .unwrap_or_else(|e| {
-                                println!("Error parsing lyrics: {}", e);
-                                // Return an empty result instead of panicking
-                                None
-                            }),
-                    );
+                // Only queue the song with the lyrics the user can see highlighted.
+                if let Some(lr) = self.state.selected_lyrics().cloned() {
+                    self.queue_song(Some(lr)).await;
                 }
 
+                return Ok(EventState::Consumed);
+            }
+            (SearchFocus::Audio, Key::Char('m')) => {
+                self.load_more_audio();
+
+                return Ok(EventState::Consumed);
+            }
+            // Quick add: queue the suggested pair in one keystroke.
+            (SearchFocus::Audio | SearchFocus::Lyrics, Key::Char('a')) => {
+                if let Some(pairing) = self.state.pairing {
+                    let ar = self.state.audio_results[pairing.audio].clone();
+                    let lr = self.state.lyric_results[pairing.lyrics].clone();
+
+                    self.state.song = Song::new().with_ar(ar);
+                    self.queue_song(Some(lr)).await;
+                }
+
+                return Ok(EventState::Consumed);
+            }
+            // Singer bindings.
+            (SearchFocus::Singer, Key::Enter) => {
+                self.add_to_queue();
+
+                return Ok(EventState::Consumed);
+            }
+            (SearchFocus::Singer, Key::Esc) => {
+                // Go back to choosing lyrics without queueing the song.
                 with_state(&self.global_state, |s| {
-                    s.song_list.push(self.state.song.clone());
                     s.mode = InputMode::Nav;
+                });
+                self.state.with_state(|s| {
+                    s.focus = SearchFocus::Lyrics;
+                });
 
-                    // Return to Home if we have more than one song or Queue to show that the
-                    // next song was added to the queue.
-                    match s.song_list.len() > 1 {
-                        true => s.focus = Focus::Queue,
-                        false => s.focus = Focus::Home,
-                    }
+                return Ok(EventState::Consumed);
+            }
+            (SearchFocus::Singer, Key::Backspace) => {
+                self.state.with_state(|s| {
+                    s.singer.handle_event(&Event::Key(KeyEvent::new(
+                        KeyCode::Backspace,
+                        KeyModifiers::NONE,
+                    )));
                 });
 
-                // Clear component state.
-                self.state.reset();
+                return Ok(EventState::Consumed);
+            }
+            (SearchFocus::Singer, Key::Char(v)) => {
+                self.state.with_state(|s| {
+                    s.singer.handle_event(&Event::Key(KeyEvent::new(
+                        KeyCode::Char(v),
+                        KeyModifiers::NONE,
+                    )));
+                });
 
                 return Ok(EventState::Consumed);
             }
@@ -364,7 +904,6 @@ where
 {
     fn render<B: Backend>(&self, f: &mut Frame, rect: Rect) -> anyhow::Result<()> {
         let width = rect.width.max(3) - 3; // keep 2 for borders and 1 for cursor
-        let scroll = self.state.query.visual_scroll(width as usize);
 
         let vert_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -373,7 +912,18 @@ where
 
         let (search, body) = (vert_chunks[0], vert_chunks[1]);
 
-        let input = Paragraph::new(self.state.query.value())
+        // The search box asks for the singer once a song has been chosen.
+        let (value, prompt, action) = match self.state.focus {
+            SearchFocus::Singer => (
+                &self.state.singer,
+                format!("Who's singing {}? ", self.state.song.title),
+                " to add to the queue)",
+            ),
+            _ => (&self.state.query, "Search for a song ".to_string(), " to submit)"),
+        };
+        let scroll = value.visual_scroll(width as usize);
+
+        let input = Paragraph::new(value.value())
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::Yellow))
             .scroll((0, scroll as u16))
@@ -382,7 +932,7 @@ where
                     .borders(Borders::ALL)
                     .title_alignment(Alignment::Center)
                     .title(Line::from(vec![
-                        Span::raw("Search for a song "),
+                        Span::raw(prompt),
                         Span::styled("(Press ", Style::default().fg(Color::DarkGray)),
                         Span::styled(
                             "ENTER",
@@ -390,7 +940,7 @@ where
                                 .add_modifier(Modifier::BOLD)
                                 .fg(Color::LightRed),
                         ),
-                        Span::styled(" to submit)", Style::default().fg(Color::DarkGray)),
+                        Span::styled(action, Style::default().fg(Color::DarkGray)),
                     ])),
             );
 
@@ -403,10 +953,57 @@ where
 
         let (audio, lyrics) = (chunks[0], chunks[1]);
 
+        let audio_title = match self.state.pairing {
+            Some(pairing) => Line::from(vec![
+                Span::raw("Audio Results "),
+                Span::styled("(Press ", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    "a",
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .fg(Color::LightRed),
+                ),
+                Span::styled(
+                    format!(" to add the best match, {:.0}%)", pairing.score * 100.0),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            None => Line::from("Audio Results"),
+        };
+        let audio_title = match self.state.audio_next_page {
+            Some(_) => {
+                let mut spans = audio_title.spans;
+                spans.push(Span::styled(
+                    " (m or \u{2193} for more)",
+                    Style::default().fg(Color::DarkGray),
+                ));
+                Line::from(spans)
+            }
+            None => audio_title,
+        };
+        // Say why scrolling past the end of the list does nothing, or what it's waiting on.
+        let audio_title = match self.state.audio_loading {
+            true if self.state.audio_results.is_empty() => self.loading(audio_title, "searching"),
+            true => self.loading(audio_title, "loading more\u{2026}"),
+            false if self.state.at_audio_end() && self.state.audio_next_page.is_none() => {
+                let mut spans = audio_title.spans;
+                spans.push(Span::styled(
+                    " no more results",
+                    Style::default().fg(Color::DarkGray),
+                ));
+                Line::from(spans)
+            }
+            false => audio_title,
+        };
+        let lyrics_title = match self.state.lyrics_loading || self.state.matching {
+            true => self.loading(Line::from("Lyrics Results"), "searching"),
+            false => Line::from("Lyrics Results"),
+        };
+
         let audio_list = List::new(get_list_items(self.state.audio_presentation_list.clone()))
             .block(
                 Block::default()
-                    .title("Audio Results")
+                    .title(audio_title)
                     .borders(Borders::ALL),
             )
             .highlight_style(Style::default().bg(Color::LightBlue).fg(Color::Black))
@@ -415,14 +1012,164 @@ where
         let lyrics_list = List::new(get_list_items(self.state.lyrics_presentation_list.clone()))
             .block(
                 Block::default()
-                    .title("Lyrics Results")
+                    .title(lyrics_title)
                     .borders(Borders::ALL),
             )
             .highlight_style(Style::default().bg(Color::LightGreen).fg(Color::Black))
             .highlight_symbol(">> ");
 
+        // Preview the highlighted lyrics below the list, so bad versions can be skipped before
+        // they're queued.
+        let highlighted = match self.state.focus {
+            SearchFocus::Lyrics => self.state.selected_lyrics(),
+            _ => None,
+        };
+        let lyrics = match highlighted {
+            Some(lr) => {
+                let lyrics_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(7), Constraint::Min(0)])
+                    .split(lyrics);
+
+                let preview = Paragraph::new(preview_lines(lr))
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().title("Preview").borders(Borders::ALL));
+                f.render_widget(preview, lyrics_chunks[1]);
+
+                lyrics_chunks[0]
+            }
+            None => lyrics,
+        };
+
         f.render_stateful_widget(audio_list, audio, &mut self.state.audio_state.clone());
         f.render_stateful_widget(lyrics_list, lyrics, &mut self.state.lyrics_state.clone());
         Ok(())
     }
 }
+
+// CHANNEL_WIDTH is how many columns of an audio row the channel name gets.
+const CHANNEL_WIDTH: usize = 20;
+// KARAOKE_WORDS mark an audio result as a karaoke (backing) track when its title or channel has
+// one of them.
+const KARAOKE_WORDS: [&str; 4] = ["karaoke", "instrumental", "backing track", "sing along"];
+
+// audio_row lays an audio result out in columns: confidence, duration, a karaoke flag, channel
+// and title. The title goes last so long ones don't push the other columns around.
+fn audio_row(confidence: Option<f64>, ar: &AudioResult) -> Line<'static> {
+    let haystack = format!("{} {}", ar.title, ar.artist).to_lowercase();
+    let karaoke = match KARAOKE_WORDS.iter().any(|word| haystack.contains(word)) {
+        true => Span::styled("karaoke ", Style::default().fg(Color::Magenta)),
+        false => Span::raw(" ".repeat(8)),
+    };
+
+    Line::from(vec![
+        confidence_span(confidence),
+        Span::styled(
+            format!("{:>6}  ", format_duration(ar.duration)),
+            Style::default().fg(Color::DarkGray),
+        ),
+        karaoke,
+        Span::styled(
+            format!("{}  ", fit(&ar.artist, CHANNEL_WIDTH)),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw(ar.title.clone()),
+    ])
+}
+
+// lyrics_row lays a lyrics result out in columns: confidence, the length of the recording the
+// lyrics were timed to, whether they are synced, and title and artist.
+fn lyrics_row(confidence: Option<f64>, lr: &LyricsResult) -> Line<'static> {
+    let sync = match (lr.synced_lyrics.is_empty(), lr.plain_lyrics.is_empty()) {
+        (false, _) => Span::styled("synced ", Style::default().fg(Color::Green)),
+        (true, false) => Span::styled("plain  ", Style::default().fg(Color::Yellow)),
+        (true, true) => Span::styled("none   ", Style::default().fg(Color::Red)),
+    };
+
+    Line::from(vec![
+        confidence_span(confidence),
+        Span::styled(
+            format!("{:>6}  ", format_duration(lr.duration)),
+            Style::default().fg(Color::DarkGray),
+        ),
+        sync,
+        Span::raw(format!("{} by {}", lr.title, lr.artist)),
+    ])
+}
+
+// confidence_span is a confidence badge colored by how sure we are of the pairing, or blank space
+// when there's nothing to pair with.
+fn confidence_span(confidence: Option<f64>) -> Span<'static> {
+    let Some(confidence) = confidence else {
+        return Span::raw(" ".repeat(5));
+    };
+
+    let color = match confidence {
+        c if c >= 0.75 => Color::Green,
+        c if c >= 0.5 => Color::Yellow,
+        _ => Color::Red,
+    };
+
+    Span::styled(format!("{:>3.0}% ", confidence * 100.0), Style::default().fg(color))
+}
+
+// format_duration formats a duration as "m:ss", or "-" when it is unknown.
+fn format_duration(duration: Duration) -> String {
+    match duration.as_secs() {
+        0 => "-".to_string(),
+        secs => format!("{}:{:02}", secs / 60, secs % 60),
+    }
+}
+
+// fit pads or truncates text to exactly width characters.
+fn fit(text: &str, width: usize) -> String {
+    match text.chars().count() > width {
+        true => format!("{}\u{2026}", text.chars().take(width - 1).collect::<String>()),
+        false => format!("{:<width$}", text),
+    }
+}
+
+// preview_lines summarizes a lyrics result: whether it is synced, how many lines it has and when
+// they start and end, followed by its first few lines.
+fn preview_lines(lr: &LyricsResult) -> Vec<Line<'static>> {
+    let doc = lrc::parse(&lr.synced_lyrics);
+    let (summary, text): (String, Vec<String>) = match (doc.lines.first(), doc.lines.last()) {
+        (Some(first), Some(last)) => (
+            format!(
+                "Synced {} {} lines {} {} to {}",
+                EMDASH,
+                doc.lines.len(),
+                EMDASH,
+                format_timestamp(first.timestamp_ms),
+                format_timestamp(last.timestamp_ms),
+            ),
+            doc.lines.into_iter().map(|line| line.lyric.text).collect(),
+        ),
+        _ => {
+            let text: Vec<String> = lr
+                .plain_lyrics
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect();
+            match text.is_empty() {
+                true => ("No lyrics".to_string(), text),
+                false => (format!("Plain {} {} lines", EMDASH, text.len()), text),
+            }
+        }
+    };
+
+    let mut lines = vec![Line::from(summary).style(Style::default().fg(Color::Yellow))];
+    lines.extend(
+        text.into_iter()
+            .take(PREVIEW_LINES)
+            .map(Line::from),
+    );
+
+    lines
+}
+
+// format_timestamp formats ms as "m:ss.xx", the way LRC files write timestamps.
+fn format_timestamp(ms: u64) -> String {
+    format!("{}:{:02}.{:02}", ms / 60_000, ms / 1000 % 60, ms % 1000 / 10)
+}
@@ -0,0 +1,130 @@
+use super::RenderableComponent;
+use crate::app::GlobalState;
+use crate::events::{EventState, Key};
+use crate::state::{with_state, Focus, InputMode};
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use std::sync::{Arc, Mutex};
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+// Seek is the "go to mm:ss" prompt shown in the footer.
+pub struct Seek {
+    global_state: Arc<Mutex<GlobalState>>,
+    input: Input,
+    target_ms: Option<u64>,
+}
+
+impl Seek {
+    pub fn new(state: Arc<Mutex<GlobalState>>) -> Self {
+        Self {
+            global_state: state,
+            input: Input::default(),
+            target_ms: None,
+        }
+    }
+
+    // take_target returns the position the user asked to go to, if one was submitted.
+    pub fn take_target(&mut self) -> Option<u64> {
+        self.target_ms.take()
+    }
+
+    fn close(&mut self) {
+        self.input.reset();
+        with_state(&self.global_state, |s| {
+            s.focus = Focus::Home;
+            s.mode = InputMode::Nav;
+        });
+    }
+
+    pub async fn event(&mut self, key: Key) -> Result<EventState> {
+        match key {
+            Key::Esc => {
+                self.close();
+            }
+            Key::Enter => {
+                self.target_ms = parse_position(self.input.value());
+                self.close();
+            }
+            Key::Backspace => {
+                self.input.handle_event(&Event::Key(KeyEvent::new(
+                    KeyCode::Backspace,
+                    KeyModifiers::NONE,
+                )));
+            }
+            Key::Char(v) => {
+                self.input.handle_event(&Event::Key(KeyEvent::new(
+                    KeyCode::Char(v),
+                    KeyModifiers::NONE,
+                )));
+            }
+            _ => return Ok(EventState::NotConsumed),
+        }
+
+        Ok(EventState::Consumed)
+    }
+}
+
+// parse_position parses "ss", "mm:ss" or "hh:mm:ss" into milliseconds.
+fn parse_position(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    value
+        .split(':')
+        .try_fold(0u64, |acc, part| Some(acc * 60 + part.trim().parse::<u64>().ok()?))
+        .map(|secs| secs * 1000)
+}
+
+impl RenderableComponent for Seek {
+    fn render<B: Backend>(&self, f: &mut Frame, rect: Rect) -> anyhow::Result<()> {
+        let input = Paragraph::new(self.input.value())
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title_alignment(Alignment::Center)
+                    .title(Line::from(vec![
+                        Span::raw("Go to mm:ss "),
+                        Span::styled("(Press ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(
+                            "ENTER",
+                            Style::default()
+                                .add_modifier(Modifier::BOLD)
+                                .fg(Color::LightRed),
+                        ),
+                        Span::styled(" to jump)", Style::default().fg(Color::DarkGray)),
+                    ])),
+            );
+
+        f.render_widget(input, rect);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_seconds_minutes_and_hours() {
+        assert_eq!(parse_position("45"), Some(45_000));
+        assert_eq!(parse_position("1:30"), Some(90_000));
+        assert_eq!(parse_position("01:02:03"), Some(3_723_000));
+        assert_eq!(parse_position(" 2 : 05 "), Some(125_000));
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        assert_eq!(parse_position(""), None);
+        assert_eq!(parse_position("   "), None);
+        assert_eq!(parse_position("1:"), None);
+        assert_eq!(parse_position("1:3x"), None);
+        assert_eq!(parse_position("-5"), None);
+    }
+}
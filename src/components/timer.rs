@@ -1,6 +1,7 @@
 use super::RenderableComponent;
 use crate::app::GlobalState;
 use crate::state::get_state;
+use crate::util::EMDASH;
 use block::Title;
 use ratatui::style::Stylize;
 use ratatui::widgets::block;
@@ -41,54 +42,56 @@ impl RenderableComponent for Timer {
 
         let (left, right) = (rects[0], rects[1]);
 
-        let s = format!(
+        let mut s = format!(
             "Singing for {:02}:{:02}",
             global_state.session_time_elapsed.as_secs() / 60,
             global_state.session_time_elapsed.as_secs() % 60,
         );
+
+        // Show the lyric offset while it's in effect.
+        let offset_ms = global_state.lyric_offset_ms;
+        if offset_ms != 0 && global_state.current_song.is_some() {
+            s.push_str(&format!(
+                " {} lyrics {:.1}s {}",
+                EMDASH,
+                offset_ms.unsigned_abs() as f64 / 1000.0,
+                if offset_ms > 0 { "early" } else { "late" },
+            ));
+        }
+        // Problems in the background take the place of the session time until they're cleared.
+        let title = match &global_state.status {
+            Some(status) => Title::from(status.clone().light_red()),
+            None => Title::from(s.dim()),
+        };
         let time_singing = Block::default()
-            .title(Title::from(s.dim()))
+            .title(title)
             .title_alignment(Alignment::Left);
         f.render_widget(time_singing, left);
 
-        // If we have another song in the queue, show the time remaining.
-        match global_state.song_list.get(0) {
-            Some(song) => {
-                if song.duration_ms == 0 {
-                    return Ok(());
-                }
-
-                // Song remaining time as ms.
-                let song_remaining_time = song.duration_ms - global_state.current_song_elapsed_ms;
-
-                if song_remaining_time == 0 {
-                    return Ok(());
-                }
-
-                let time_remaining = format!(
-                    " {:02}:{:02}",
-                    song_remaining_time / 60_000,
-                    (song_remaining_time % 60_000) / 1000,
-                );
-
-                let next_song = global_state.song_list.get(0).unwrap();
-                let song_remaining = format!(
-                    " until {} by {}",
-                    next_song.title,
-                    next_song.artist,
-                );
-
-                let time_to_next = Block::default()
-                    .title(Title::from(
-                        format!("{}{}", time_remaining, song_remaining).dim(),
-                    ))
-                    .title_alignment(Alignment::Right);
+        // If we have another song in the queue, show who's up next and, while a song is playing,
+        // how long until they're on.
+        let Some(up_next) = global_state.up_next() else {
+            return Ok(());
+        };
 
-                f.render_widget(time_to_next, right);
+        let time_remaining = match &global_state.current_song {
+            Some(song) if song.duration_ms > global_state.current_song_elapsed_ms => {
+                let remaining_ms = song.duration_ms - global_state.current_song_elapsed_ms;
+                format!(
+                    " in {:02}:{:02}",
+                    remaining_ms / 60_000,
+                    (remaining_ms % 60_000) / 1000,
+                )
             }
-            _ => {}
+            _ => String::new(),
         };
 
+        let time_to_next = Block::default()
+            .title(Title::from(format!("{}{}", up_next, time_remaining).dim()))
+            .title_alignment(Alignment::Right);
+
+        f.render_widget(time_to_next, right);
+
         Ok(())
     }
 }
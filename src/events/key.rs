@@ -26,6 +26,10 @@ pub enum Key {
     Up,
     /// Down arrow
     Down,
+    /// Shift + Up arrow
+    ShiftUp,
+    /// Shift + Down arrow
+    ShiftDown,
 
     /// Insert key
     Ins,
@@ -113,6 +117,8 @@ impl fmt::Display for Key {
             Key::Right => write!(f, "\u{2192}"), //→
             Key::Up => write!(f, "\u{2191}"),    //↑
             Key::Down => write!(f, "\u{2193}"),  //↓
+            Key::ShiftUp => write!(f, "<Shift+\u{2191}>"),
+            Key::ShiftDown => write!(f, "<Shift+\u{2193}>"),
             Key::Enter
             | Key::Tab
             | Key::Backspace
@@ -147,6 +153,16 @@ impl From<event::KeyEvent> for Key {
                 code: event::KeyCode::Right,
                 ..
             } => Key::Right,
+            event::KeyEvent {
+                code: event::KeyCode::Up,
+                modifiers: event::KeyModifiers::SHIFT,
+                ..
+            } => Key::ShiftUp,
+            event::KeyEvent {
+                code: event::KeyCode::Down,
+                modifiers: event::KeyModifiers::SHIFT,
+                ..
+            } => Key::ShiftDown,
             event::KeyEvent {
                 code: event::KeyCode::Up,
                 ..
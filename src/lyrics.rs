@@ -1,17 +1,28 @@
 use crate::util::deserialize_u64;
 use async_trait::async_trait;
+use std::time::Duration;
 
+pub(crate) mod layered;
+pub(crate) mod local;
+pub(crate) mod lrc;
 pub(crate) mod lrclib;
 
-type LyricsMap = std::collections::BTreeMap<u64, String>;
+use crate::models::song::{LyricLine, LyricsMap};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LyricsResult {
     pub id: String,
     pub title: String,
     pub artist: String,
+    pub album: String,
+    // duration is the length of the recording the lyrics were timed against, or zero if unknown.
+    pub duration: Duration,
     pub synced_lyrics: String,
+    // plain_lyrics are the unsynced lyrics, shown when there are no synced ones.
+    pub plain_lyrics: String,
     pub lyric_map: Option<LyricsMap>,
+    // local is whether the lyrics come from a hand-made .lrc file, which wins over LRCLIB.
+    pub local: bool,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -21,9 +32,10 @@ pub struct LyricResponse {
     pub(crate) id: String,
     pub(crate) track_name: String,
     pub(crate) artist_name: String,
-    _album_name: String,
+    pub(crate) album_name: Option<String>,
+    pub(crate) duration: Option<f64>,
     _instrumental: bool,
-    _plain_lyrics: Option<String>,
+    pub(crate) plain_lyrics: Option<String>,
     pub(crate) synced_lyrics: Option<String>,
     // TODO: Is this even possible?
     pub(crate) message: Option<Message>,
@@ -45,12 +57,137 @@ pub struct Lyric {
     pub(crate) synced_lyrics: String,
 }
 
+// LyricsSignature identifies one recording of a song, the way LRCLIB's /api/get does.
+#[derive(Debug, Clone, Default)]
+pub struct LyricsSignature {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub duration: Duration,
+}
+
 #[async_trait]
 pub trait LyricsFetcher {
     async fn search(&self, query: &str) -> anyhow::Result<Vec<LyricsResult>>;
-    async fn parse(&self, synced: String) -> anyhow::Result<Option<crate::models::song::LyricsMap>>;
+    async fn parse(&self, synced: String) -> anyhow::Result<Option<LyricsMap>>;
+
+    // get looks up the lyrics matching signature exactly. Fetchers that can't do exact lookups
+    // find nothing.
+    async fn get(&self, _signature: &LyricsSignature) -> anyhow::Result<Option<LyricsResult>> {
+        Ok(None)
+    }
+
+    // get_by_id looks up lyrics by the id a search gave them, e.g. to reload a saved playlist.
+    // Fetchers that don't know the id find nothing.
+    async fn get_by_id(&self, _id: &str) -> anyhow::Result<Option<LyricsResult>> {
+        Ok(None)
+    }
+}
+
+// rank_by_duration sorts results so the ones whose duration is closest to the audio's come first.
+// Lyrics timed against a different cut of the song drift out of sync, so this is the best signal
+// we have for picking a version. Results without a duration go last. Local files are ranked among
+// themselves, ahead of LRCLIB's results, since many have no [length:] tag to rank them by.
+pub fn rank_by_duration(results: &mut [LyricsResult], duration: Duration) {
+    if duration.is_zero() {
+        return;
+    }
+
+    results.sort_by_key(|r| {
+        let gap = match r.duration.is_zero() {
+            true => Duration::MAX,
+            false => r.duration.abs_diff(duration),
+        };
+        (!r.local, gap)
+    });
+}
+
+// insert_exact_match puts an exact match at the top of results, in place of its copy among them.
+// Local files still go first, so an exact LRCLIB match comes straight after them.
+pub fn insert_exact_match(results: &mut Vec<LyricsResult>, exact: LyricsResult) {
+    results.retain(|r| r.id != exact.id);
+
+    let index = match exact.local {
+        true => 0,
+        false => results.iter().take_while(|r| r.local).count(),
+    };
+    results.insert(index, exact);
 }
 
 pub trait LyricsService: Send + Sync {
-    fn play(&self, elapsed_time_ms: u64, lyrics_map: LyricsMap) -> anyhow::Result<Vec<String>>;
-}
\ No newline at end of file
+    fn play(&self, elapsed_time_ms: u64, lyrics_map: &LyricsMap) -> anyhow::Result<Vec<LyricLine>>;
+}
+
+// lyrics_result is a result for tests, titled and identified by title, with nothing else set.
+#[cfg(test)]
+pub(crate) fn lyrics_result(title: &str, artist: &str, secs: u64) -> LyricsResult {
+    LyricsResult {
+        id: title.to_string(),
+        title: title.to_string(),
+        artist: artist.to_string(),
+        duration: Duration::from_secs(secs),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, secs: u64, local: bool) -> LyricsResult {
+        LyricsResult {
+            local,
+            ..lyrics_result(id, "", secs)
+        }
+    }
+
+    fn ids(results: &[LyricsResult]) -> Vec<&str> {
+        results.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_by_duration_with_local_files_first() {
+        let mut results = vec![
+            result("far", 260, false),
+            result("untimed", 0, false),
+            result("close", 201, false),
+            result("local-untimed", 0, true),
+            result("local-far", 230, true),
+        ];
+
+        rank_by_duration(&mut results, Duration::from_secs(200));
+
+        assert_eq!(ids(&results), ["local-far", "local-untimed", "close", "far", "untimed"]);
+    }
+
+    #[test]
+    fn leaves_results_alone_without_a_duration() {
+        let mut results = vec![result("b", 100, false), result("a", 0, true)];
+
+        rank_by_duration(&mut results, Duration::ZERO);
+
+        assert_eq!(ids(&results), ["b", "a"]);
+    }
+
+    #[test]
+    fn inserts_an_exact_match_after_local_files() {
+        let mut results = vec![
+            result("local", 0, true),
+            result("close", 201, false),
+            result("exact", 200, false),
+        ];
+
+        insert_exact_match(&mut results, result("exact", 200, false));
+
+        assert_eq!(ids(&results), ["local", "exact", "close"]);
+    }
+
+    #[test]
+    fn inserts_an_exact_match_first_without_local_files() {
+        let mut results = vec![result("close", 201, false)];
+
+        insert_exact_match(&mut results, result("exact", 200, false));
+
+        assert_eq!(ids(&results), ["exact", "close"]);
+    }
+}
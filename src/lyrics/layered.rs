@@ -0,0 +1,68 @@
+use crate::lyrics::{LyricsFetcher, LyricsResult, LyricsSignature};
+use crate::models::song::LyricsMap;
+use async_trait::async_trait;
+
+// Layered searches two lyrics fetchers at once and lists the primary's results first, so e.g.
+// hand-corrected local files win over LRCLIB. If one of them fails (say we're offline), the other's
+// results are still returned.
+#[derive(Debug, Clone)]
+pub struct Layered<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P, F> Layered<P, F> {
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+#[async_trait]
+impl<P, F> LyricsFetcher for Layered<P, F>
+where
+    P: LyricsFetcher + Send + Sync,
+    F: LyricsFetcher + Send + Sync,
+{
+    async fn search(&self, query: &str) -> anyhow::Result<Vec<LyricsResult>> {
+        let (primary, fallback) =
+            futures::join!(self.primary.search(query), self.fallback.search(query));
+
+        match (primary, fallback) {
+            (Ok(mut primary), Ok(fallback)) => {
+                primary.extend(fallback);
+                Ok(primary)
+            }
+            (Ok(results), Err(e)) | (Err(e), Ok(results)) => {
+                println!("Error searching lyrics: {}", e);
+                Ok(results)
+            }
+            (Err(e), Err(_)) => Err(e),
+        }
+    }
+
+    async fn parse(&self, synced: String) -> anyhow::Result<Option<LyricsMap>> {
+        self.primary.parse(synced).await
+    }
+
+    async fn get(&self, signature: &LyricsSignature) -> anyhow::Result<Option<LyricsResult>> {
+        match self.primary.get(signature).await {
+            Ok(Some(result)) => Ok(Some(result)),
+            Ok(None) => self.fallback.get(signature).await,
+            Err(e) => {
+                println!("Error looking up lyrics: {}", e);
+                self.fallback.get(signature).await
+            }
+        }
+    }
+
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<LyricsResult>> {
+        match self.primary.get_by_id(id).await {
+            Ok(Some(result)) => Ok(Some(result)),
+            Ok(None) => self.fallback.get_by_id(id).await,
+            Err(e) => {
+                println!("Error looking up lyrics: {}", e);
+                self.fallback.get_by_id(id).await
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use crate::lyrics::lrc;
+use crate::lyrics::lrclib::LRCLib;
+use crate::lyrics::{LyricsFetcher, LyricsResult};
+use crate::models::song::LyricsMap;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const LRC_EXTENSION: &str = "lrc";
+
+#[derive(Debug, Clone, Default)]
+struct LrcFile {
+    path: PathBuf,
+    title: String,
+    artist: String,
+    album: String,
+    // duration comes from the file's [length:] tag, or is zero without one.
+    duration: Duration,
+    // haystack is the lowercased text searches are matched against.
+    haystack: String,
+}
+
+impl LrcFile {
+    // read loads the file's lyrics as a search result.
+    fn read(&self) -> Option<LyricsResult> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(synced) => Some(LyricsResult {
+                id: self.path.to_string_lossy().to_string(),
+                title: self.title.clone(),
+                artist: self.artist.clone(),
+                album: self.album.clone(),
+                duration: self.duration,
+                synced_lyrics: synced,
+                plain_lyrics: String::new(),
+                lyric_map: None,
+                local: true,
+            }),
+            Err(e) => {
+                println!("Failed to read {}: {}", self.path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+// LocalLyrics serves hand-made .lrc files from one or more directories, e.g. a lyrics folder or
+// the local music library (so sidecar files next to the audio are picked up). The directories are
+// indexed once, when the fetcher is created. Directories that can't be read are skipped, so a
+// missing lyrics folder doesn't stop the app from starting.
+#[derive(Debug, Clone, Default)]
+pub struct LocalLyrics {
+    files: Arc<Vec<LrcFile>>,
+}
+
+impl LocalLyrics {
+    pub(crate) fn new(dirs: Vec<PathBuf>) -> Self {
+        let mut files = Vec::new();
+        for dir in dirs {
+            if let Err(e) = index_dir(&dir, &mut files) {
+                println!("Skipping lyrics in {}: {}", dir.display(), e);
+            }
+        }
+
+        Self {
+            files: Arc::new(files),
+        }
+    }
+}
+
+// index_dir walks dir recursively, adding every .lrc file it finds to files.
+fn index_dir(dir: &Path, files: &mut Vec<LrcFile>) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            index_dir(&path, files)?;
+            continue;
+        }
+
+        let is_lrc = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(LRC_EXTENSION));
+        if !is_lrc {
+            continue;
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => files.push(read_lrc_file(path, &contents)),
+            Err(e) => {
+                println!("Failed to read {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// read_lrc_file takes the artist and title from the file's [ar:] and [ti:] tags, falling back to
+// an "Artist - Title.lrc" file name.
+fn read_lrc_file(path: PathBuf, contents: &str) -> LrcFile {
+    let metadata = lrc::parse(contents).metadata;
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (stem_artist, stem_title) = match stem.split_once(" - ") {
+        Some((artist, title)) => (artist.trim().to_string(), title.trim().to_string()),
+        None => (String::new(), stem.clone()),
+    };
+
+    let title = metadata.title.unwrap_or(stem_title);
+    let artist = metadata.artist.unwrap_or(stem_artist);
+    let album = metadata.album.unwrap_or_default();
+    let haystack = format!("{} {} {} {}", stem, title, artist, album).to_lowercase();
+
+    LrcFile {
+        path,
+        title,
+        artist,
+        album,
+        duration: metadata.length.unwrap_or_default(),
+        haystack,
+    }
+}
+
+#[async_trait]
+impl LyricsFetcher for LocalLyrics {
+    async fn search(&self, query: &str) -> anyhow::Result<Vec<LyricsResult>> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect();
+
+        let lyrics = self
+            .files
+            .iter()
+            .filter(|file| terms.iter().all(|term| file.haystack.contains(term)))
+            .filter_map(LrcFile::read)
+            .collect();
+
+        Ok(lyrics)
+    }
+
+    // Local lyrics are identified by their file's path.
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<LyricsResult>> {
+        Ok(self
+            .files
+            .iter()
+            .find(|file| file.path.to_string_lossy() == id)
+            .and_then(LrcFile::read))
+    }
+
+    async fn parse(&self, synced: String) -> anyhow::Result<Option<LyricsMap>> {
+        // Local files sync exactly like LRCLIB's.
+        LRCLib::new().parse(synced).await
+    }
+}
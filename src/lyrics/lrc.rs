@@ -0,0 +1,409 @@
+use crate::models::song::{LyricLine, LyricWord};
+use std::time::Duration;
+
+// LrcDocument is a parsed LRC file: its ID tags and its timed lines, sorted by time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LrcDocument {
+    pub metadata: LrcMetadata,
+    pub lines: Vec<LrcLine>,
+}
+
+// LrcMetadata holds the ID tags found in an LRC file's header.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LrcMetadata {
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub album: Option<String>,
+    pub author: Option<String>,
+    pub length: Option<Duration>,
+    // offset_ms is the [offset:] tag. It has already been applied to every line's timestamp.
+    pub offset_ms: i64,
+}
+
+// LrcLine is a lyric line and the time it starts at. The line's text has any word timestamps
+// stripped out, and its words hold the word timings from Enhanced LRC, or are empty without them.
+// Each word keeps its surrounding whitespace, so a line's words join up into its text, and a
+// trailing timestamp with no text marks when the last word ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LrcLine {
+    pub timestamp_ms: u64,
+    pub lyric: LyricLine,
+}
+
+// parse parses LRC-formatted lyrics. It is lenient: anything it doesn't understand is skipped, so
+// the worst a malformed file can do is lose lines.
+//
+// It handles the quirks seen in the wild:
+//   - fractions of any precision, so [00:12.3], [00:12.34] and [00:12.345] are all 12.3s-ish,
+//     as is the colon-separated [00:12:34];
+//   - timestamps without a fraction, e.g. [01:05];
+//   - several timestamps sharing one line, e.g. [00:12.00][01:30.00]chorus;
+//   - Enhanced LRC word timestamps, e.g. [00:12.00]<00:12.00>sing <00:12.40>along;
+//   - the [ar:], [ti:], [al:], [au:], [length:] and [offset:] ID tags;
+//   - a byte order mark, CRLF line endings and stray whitespace.
+pub fn parse(input: &str) -> LrcDocument {
+    let mut doc = LrcDocument::default();
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    for line in input.lines() {
+        parse_line(line.trim(), &mut doc);
+    }
+
+    // A positive offset makes the lyrics appear sooner.
+    let offset_ms = doc.metadata.offset_ms;
+    for line in doc.lines.iter_mut() {
+        line.timestamp_ms = line.timestamp_ms.saturating_add_signed(-offset_ms);
+        for word in line.lyric.words.iter_mut() {
+            word.start_ms = word.start_ms.saturating_add_signed(-offset_ms);
+        }
+    }
+
+    // Lines with several timestamps leave the list out of order. The sort is stable, so lines
+    // sharing a timestamp keep their order in the file.
+    doc.lines.sort_by_key(|line| line.timestamp_ms);
+
+    doc
+}
+
+fn parse_line(line: &str, doc: &mut LrcDocument) {
+    let mut rest = line;
+    let mut timestamps = Vec::new();
+
+    while let Some(tag) = rest.strip_prefix('[') {
+        let Some(end) = tag.find(']') else {
+            break;
+        };
+        let (content, remainder) = (&tag[..end], &tag[end + 1..]);
+
+        match parse_timestamp(content) {
+            Some(timestamp_ms) => timestamps.push(timestamp_ms),
+            // ID tags sit on a line of their own.
+            None if timestamps.is_empty() => {
+                parse_tag(content, &mut doc.metadata);
+                return;
+            }
+            // Anything else is part of the lyric, e.g. "[00:12.00][Chorus] ...".
+            None => break,
+        }
+
+        rest = remainder.trim_start();
+    }
+
+    for timestamp_ms in timestamps {
+        let (text, words) = parse_words(rest, timestamp_ms);
+
+        doc.lines.push(LrcLine {
+            timestamp_ms,
+            lyric: LyricLine { text, words },
+        });
+    }
+}
+
+// parse_words splits an Enhanced LRC line into its words. Lines without word timestamps have no
+// words. Any text before the first word timestamp starts with the line itself.
+fn parse_words(text: &str, line_ms: u64) -> (String, Vec<LyricWord>) {
+    let mut words = Vec::new();
+    let mut timed = false;
+    let mut start_ms = line_ms;
+    let mut rest = text;
+    let mut word = String::new();
+
+    while let Some(open) = rest.find('<') {
+        let tag = &rest[open + 1..];
+        let timestamp_ms = tag
+            .find('>')
+            .and_then(|close| Some((parse_timestamp(&tag[..close])?, close)));
+
+        match timestamp_ms {
+            Some((timestamp_ms, close)) => {
+                timed = true;
+                word.push_str(&rest[..open]);
+                if !word.is_empty() {
+                    words.push(LyricWord {
+                        start_ms,
+                        text: std::mem::take(&mut word),
+                    });
+                }
+
+                start_ms = timestamp_ms;
+                rest = &tag[close + 1..];
+            }
+            // Not a timestamp, so the '<' is part of the lyric.
+            None => {
+                word.push_str(&rest[..=open]);
+                rest = tag;
+            }
+        }
+    }
+
+    // Without any word timestamps, this is a plain line.
+    if !timed {
+        return (text.trim().to_string(), words);
+    }
+
+    word.push_str(rest);
+    words.push(LyricWord {
+        start_ms,
+        text: word,
+    });
+
+    let text = words.iter().map(|w| w.text.as_str()).collect::<String>();
+    (text.trim().to_string(), words)
+}
+
+fn parse_tag(content: &str, metadata: &mut LrcMetadata) {
+    let Some((key, value)) = content.split_once(':') else {
+        return;
+    };
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+
+    match key.trim().to_lowercase().as_str() {
+        "ar" => metadata.artist = Some(value.to_string()),
+        "ti" => metadata.title = Some(value.to_string()),
+        "al" => metadata.album = Some(value.to_string()),
+        "au" => metadata.author = Some(value.to_string()),
+        "length" => metadata.length = parse_timestamp(value).map(Duration::from_millis),
+        "offset" => {
+            if let Ok(offset_ms) = value.trim_start_matches('+').parse() {
+                metadata.offset_ms = offset_ms;
+            }
+        }
+        _ => {}
+    }
+}
+
+// parse_timestamp parses "mm:ss", "mm:ss.f" (with any number of fraction digits) or "mm:ss:ff"
+// into milliseconds.
+fn parse_timestamp(content: &str) -> Option<u64> {
+    let (minutes, rest) = content.trim().split_once(':')?;
+    let (seconds, fraction) = match rest.split_once(['.', ':']) {
+        Some((seconds, fraction)) => (seconds, Some(fraction)),
+        None => (rest, None),
+    };
+
+    let minutes: u64 = parse_digits(minutes)?;
+    let seconds: u64 = parse_digits(seconds)?;
+    if seconds >= 60 {
+        return None;
+    }
+
+    // The fraction is a decimal fraction of a second, not a count of milliseconds: .3 is 300ms
+    // and .34 is 340ms. Anything past millisecond precision is dropped.
+    let millis = match fraction {
+        Some(fraction) => {
+            parse_digits(fraction)?;
+            let digits: String = fraction.chars().chain("000".chars()).take(3).collect();
+            digits.parse().ok()?
+        }
+        None => 0,
+    };
+
+    Some(minutes * 60_000 + seconds * 1000 + millis)
+}
+
+fn parse_digits(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamps(doc: &LrcDocument) -> Vec<(u64, &str)> {
+        doc.lines
+            .iter()
+            .map(|line| (line.timestamp_ms, line.lyric.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn fractions_are_decimal_not_milliseconds() {
+        let doc = parse("[00:12.34]two digits\n[00:13.5]one digit\n[00:14.678]three digits");
+
+        assert_eq!(
+            timestamps(&doc),
+            vec![
+                (12_340, "two digits"),
+                (13_500, "one digit"),
+                (14_678, "three digits"),
+            ]
+        );
+    }
+
+    #[test]
+    fn excess_fraction_digits_are_truncated() {
+        let doc = parse("[00:01.23456]too precise");
+
+        assert_eq!(timestamps(&doc), vec![(1_234, "too precise")]);
+    }
+
+    #[test]
+    fn timestamps_without_fraction_or_with_colon_separator() {
+        let doc = parse("[01:05]no fraction\n[01:06:50]colon fraction");
+
+        assert_eq!(
+            timestamps(&doc),
+            vec![(65_000, "no fraction"), (66_500, "colon fraction")]
+        );
+    }
+
+    #[test]
+    fn long_songs_have_minutes_past_an_hour() {
+        let doc = parse("[75:00.00]still going");
+
+        assert_eq!(timestamps(&doc), vec![(4_500_000, "still going")]);
+    }
+
+    #[test]
+    fn repeated_lines_share_timestamps() {
+        let doc = parse("[00:12.00][01:30.00]chorus\n[00:20.00]verse");
+
+        assert_eq!(
+            timestamps(&doc),
+            vec![(12_000, "chorus"), (20_000, "verse"), (90_000, "chorus")]
+        );
+    }
+
+    #[test]
+    fn parses_enhanced_word_timestamps() {
+        let doc = parse("[00:12.00]<00:12.00>Sing <00:12.50>a<00:12.80>long <00:13.40>");
+        let line = &doc.lines[0];
+
+        assert_eq!(line.lyric.text, "Sing along");
+        assert_eq!(
+            line.lyric.words,
+            vec![
+                LyricWord { start_ms: 12_000, text: "Sing ".to_string() },
+                LyricWord { start_ms: 12_500, text: "a".to_string() },
+                LyricWord { start_ms: 12_800, text: "long ".to_string() },
+                LyricWord { start_ms: 13_400, text: "".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn untimed_text_before_first_word_starts_with_line() {
+        let doc = parse("[00:01.00]Oh <00:01.50>yeah");
+
+        assert_eq!(
+            doc.lines[0].lyric.words,
+            vec![
+                LyricWord { start_ms: 1_000, text: "Oh ".to_string() },
+                LyricWord { start_ms: 1_500, text: "yeah".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn plain_lines_have_no_words() {
+        let doc = parse("[00:01.00]a <3 b");
+
+        assert_eq!(doc.lines[0].lyric.text, "a <3 b");
+        assert!(doc.lines[0].lyric.words.is_empty());
+    }
+
+    #[test]
+    fn offset_applies_to_words() {
+        let doc = parse("[offset:500]\n[00:10.00]<00:10.00>one <00:11.00>two");
+        let starts: Vec<u64> = doc.lines[0].lyric.words.iter().map(|w| w.start_ms).collect();
+
+        assert_eq!(starts, vec![9_500, 10_500]);
+    }
+
+    #[test]
+    fn reads_id_tags() {
+        let doc = parse(
+            "[ar: Queen]\n[ti:Bohemian Rhapsody]\n[al:A Night at the Opera]\n[au:Freddie Mercury]\n\
+             [length: 05:55]\n[by:someone]\n[00:00.50]Is this the real life?",
+        );
+
+        assert_eq!(
+            doc.metadata,
+            LrcMetadata {
+                artist: Some("Queen".to_string()),
+                title: Some("Bohemian Rhapsody".to_string()),
+                album: Some("A Night at the Opera".to_string()),
+                author: Some("Freddie Mercury".to_string()),
+                length: Some(Duration::from_secs(355)),
+                offset_ms: 0,
+            }
+        );
+        assert_eq!(timestamps(&doc), vec![(500, "Is this the real life?")]);
+    }
+
+    #[test]
+    fn tags_are_case_insensitive() {
+        let doc = parse("[AR:Abba]\n[Ti:Waterloo]");
+
+        assert_eq!(doc.metadata.artist.as_deref(), Some("Abba"));
+        assert_eq!(doc.metadata.title.as_deref(), Some("Waterloo"));
+    }
+
+    #[test]
+    fn positive_offset_makes_lyrics_sooner() {
+        let doc = parse("[offset:+500]\n[00:10.00]line");
+
+        assert_eq!(doc.metadata.offset_ms, 500);
+        assert_eq!(timestamps(&doc), vec![(9_500, "line")]);
+    }
+
+    #[test]
+    fn negative_offset_makes_lyrics_later() {
+        let doc = parse("[00:10.00]line\n[offset:-250]");
+
+        assert_eq!(timestamps(&doc), vec![(10_250, "line")]);
+    }
+
+    #[test]
+    fn offset_does_not_go_below_zero() {
+        let doc = parse("[offset:1000]\n[00:00.20]first");
+
+        assert_eq!(timestamps(&doc), vec![(0, "first")]);
+    }
+
+    #[test]
+    fn handles_bom_crlf_and_whitespace() {
+        let doc = parse("\u{feff}[ti:Song]\r\n  [00:01.00]  padded  \r\n\r\n[00:02.00]next\r\n");
+
+        assert_eq!(doc.metadata.title.as_deref(), Some("Song"));
+        assert_eq!(timestamps(&doc), vec![(1_000, "padded"), (2_000, "next")]);
+    }
+
+    #[test]
+    fn keeps_empty_lines_for_instrumental_breaks() {
+        let doc = parse("[00:01.00]sing\n[00:05.00]\n[00:09.00]sing again");
+
+        assert_eq!(
+            timestamps(&doc),
+            vec![(1_000, "sing"), (5_000, ""), (9_000, "sing again")]
+        );
+    }
+
+    #[test]
+    fn brackets_after_timestamps_are_lyrics() {
+        let doc = parse("[00:01.00][Chorus] la la la");
+
+        assert_eq!(timestamps(&doc), vec![(1_000, "[Chorus] la la la")]);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let doc = parse(
+            "plain text\n[00:xx.00]bad minutes\n[00:75.00]bad seconds\n[00:01.00\n[]\n[00:03.00]good",
+        );
+
+        assert_eq!(timestamps(&doc), vec![(3_000, "good")]);
+    }
+
+    #[test]
+    fn empty_input_is_empty_document() {
+        assert_eq!(parse(""), LrcDocument::default());
+    }
+}
@@ -1,9 +1,11 @@
-use crate::lyrics::{LyricResponse, LyricsFetcher, LyricsResult, LyricsService};
-use crate::models::song::LyricsMap;
+use crate::lyrics::lrc;
+use crate::lyrics::{LyricResponse, LyricsFetcher, LyricsResult, LyricsService, LyricsSignature};
+use crate::models::song::{LyricLine, LyricsMap};
 use async_trait::async_trait;
-use regex::Regex;
-use reqwest::Client;
+use std::ops::Bound;
+use reqwest::{Client, StatusCode};
 use serde_json::Value;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct LRCLib;
@@ -14,6 +16,28 @@ impl LRCLib {
     }
 }
 
+impl From<LyricResponse> for LyricsResult {
+    fn from(l: LyricResponse) -> Self {
+        LyricsResult {
+            id: l.id.to_string(),
+            artist: l.artist_name,
+            title: l.track_name,
+            album: l.album_name.unwrap_or_default(),
+            duration: l
+                .duration
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .map(Duration::from_secs_f64)
+                .unwrap_or_default(),
+            // Again, we should handle this case better elsewhere, this is placeholder
+            // and is never read by the application using this data.
+            synced_lyrics: l.synced_lyrics.unwrap_or("".to_owned()),
+            plain_lyrics: l.plain_lyrics.unwrap_or_default(),
+            lyric_map: None,
+            local: false,
+        }
+    }
+}
+
 #[async_trait]
 impl LyricsFetcher for LRCLib {
     async fn search(&self, query: &str) -> anyhow::Result<Vec<LyricsResult>> {
@@ -30,8 +54,7 @@ impl LyricsFetcher for LRCLib {
             .get(&url)
             .header("Referer", "https://lrclib.net") // Add referer header
             .send()
-            .await
-            .expect("should get lrclib response"); // Send the HTTP GET request
+            .await?; // Send the HTTP GET request
 
         // TODO: We can do this with better error handling
         // if !response.status().is_success() {
@@ -40,10 +63,7 @@ impl LyricsFetcher for LRCLib {
         //     return Err(anyhow::anyhow!("API request failed"));
         // }
 
-        let json: Vec<Value> = response
-            .json()
-            .await
-            .expect("should parse the value as json"); // Parse the response body as JSON array
+        let json: Vec<Value> = response.json().await?; // Parse the response body as JSON array
 
         let lyrics = json
             .iter()
@@ -55,49 +75,78 @@ impl LyricsFetcher for LRCLib {
                     None
                 }
             })
-            .map(|l| {
-                let synced = l.synced_lyrics.clone();
-                LyricsResult {
-                    id: l.id.to_string(),
-                    artist: l.artist_name,
-                    title: l.track_name,
-                    // Again, we should handle this case better elsewhere, this is placeholder
-                    // and is never read by the application using this data.
-                    synced_lyrics: l.synced_lyrics.unwrap_or("".to_owned()),
-                    lyric_map: None,
-                }
-            })
+            .map(LyricsResult::from)
             .collect();
 
         Ok(lyrics)
     }
 
+    async fn get(&self, signature: &LyricsSignature) -> anyhow::Result<Option<LyricsResult>> {
+        if signature.title.is_empty() || signature.duration.is_zero() {
+            return Ok(None);
+        }
+
+        // LRCLIB matches the duration to within a couple of seconds. The album is left out when
+        // we don't know it, rather than asking for an album with no name.
+        let duration = signature.duration.as_secs().to_string();
+        let mut query = vec![
+            ("track_name", signature.title.as_str()),
+            ("artist_name", signature.artist.as_str()),
+            ("duration", duration.as_str()),
+        ];
+        if !signature.album.is_empty() {
+            query.push(("album_name", signature.album.as_str()));
+        }
+
+        let response = Client::new()
+            .get("https://lrclib.net/api/get")
+            .header("Referer", "https://lrclib.net")
+            .query(&query)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let lyric: LyricResponse = response.error_for_status()?.json().await?;
+
+        Ok(Some(lyric.into()))
+    }
+
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<LyricsResult>> {
+        // LRCLIB's ids are numbers; anything else came from another fetcher.
+        let Ok(id) = id.parse::<u64>() else {
+            return Ok(None);
+        };
+
+        let response = Client::new()
+            .get(format!("https://lrclib.net/api/get/{}", id))
+            .header("Referer", "https://lrclib.net")
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let lyric: LyricResponse = response.error_for_status()?.json().await?;
+
+        Ok(Some(lyric.into()))
+    }
+
     async fn parse(&self, synced: String) -> anyhow::Result<Option<LyricsMap>> {
-        // Create regex to extract timestamp and text
-        let re = Regex::new(r"^\[(\d+):(\d+)\.(\d+)\]\s*(.*)$")?;
-
-        // Create a map to store timestamp -> lyric pairs
-        let mut time_to_lyric = LyricsMap::new();
-
-        // Process each line
-        for line in synced.lines() {
-            if let Some(captures) = re.captures(line) {
-                // Convert timestamp parts to milliseconds as u64
-                let minutes: u64 = captures[1].parse()?;
-                let seconds: u64 = captures[2].parse()?;
-                let milliseconds: u64 = captures[3].parse()?;
-
-                // Calculate total milliseconds
-                let timestamp_ms = minutes * 60_000 + seconds * 1000 + milliseconds;
-                // Round to the nearest 200ms.
-                let timestamp_ms = (timestamp_ms / 200) * 200;
-
-                // Get the lyric text
-                let lyric_text = captures[4].to_string();
-
-                // Store in map
-                time_to_lyric.insert(timestamp_ms, lyric_text);
-            }
+        let doc = lrc::parse(&synced);
+
+        let time_to_lyric: LyricsMap = doc
+            .lines
+            .into_iter()
+            .map(|line| (line.timestamp_ms, line.lyric))
+            .collect();
+
+        // Songs with only plain lyrics have nothing to sync.
+        if time_to_lyric.is_empty() {
+            return Ok(None);
         }
 
         Ok(Some(time_to_lyric))
@@ -105,15 +154,15 @@ impl LyricsFetcher for LRCLib {
 }
 
 impl LyricsService for LRCLib {
-    fn play(&self, elapsed_time_ms: u64, lyrics_map: LyricsMap) -> anyhow::Result<Vec<String>> {
+    fn play(&self, elapsed_time_ms: u64, lyrics_map: &LyricsMap) -> anyhow::Result<Vec<LyricLine>> {
         let mut result = Vec::new();
         let mut current_ts = 0;
-        let mut current_lyric = "".to_string();
+        let mut current_lyric = LyricLine::default();
 
         // Get the current lyric.
         if let Some((ts, current)) = lyrics_map.range(..=elapsed_time_ms).next_back() {
             current_ts = ts.clone();
-            current_lyric = current.to_string();
+            current_lyric = current.clone();
         }
         
         // Fetch the previous lyric (closest time before the current time) and push it to the result.
@@ -121,18 +170,20 @@ impl LyricsService for LRCLib {
         if let Some((_, prev)) = prev_lyric {
             result.push(prev.clone());
         } else {
-            result.push("".to_string()); // No previous lyric
+            result.push(LyricLine::default()); // No previous lyric
         }
 
         // Push the current lyric to the result.
         result.push(current_lyric);
 
         // Fetch the next lyric (closest time after the current time)
-        let next_lyric = lyrics_map.range(elapsed_time_ms..).next();
+        let next_lyric = lyrics_map
+            .range((Bound::Excluded(elapsed_time_ms), Bound::Unbounded))
+            .next();
         if let Some((_, next)) = next_lyric {
             result.push(next.clone());
         } else {
-            result.push("".to_string()); // No next lyric
+            result.push(LyricLine::default()); // No next lyric
         }
 
         Ok(result)
@@ -13,9 +13,17 @@ mod app;
 mod state;
 mod audio;
 mod lyrics;
-
-use crate::audio::youtube::YouTube;
+mod storage;
+
+use crate::audio::local::LocalLibrary;
+use crate::audio::player::Player;
+use crate::audio::youtube::{YouTube, DEFAULT_PAGE_SIZE};
+use crate::audio::ytdlp::{self, YtDlp};
+use crate::audio::{AudioFetcher, AudioService};
+use crate::lyrics::layered::Layered;
+use crate::lyrics::local::LocalLyrics;
 use crate::lyrics::lrclib::LRCLib;
+use crate::lyrics::{LyricsFetcher, LyricsService};
 use anyhow::Result;
 use app::AppComponent;
 use crossterm::{
@@ -26,16 +34,97 @@ use dotenv::dotenv;
 use events::{Event, Events, Key};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 // APP_TICK_RATE is the rate in ms at which the app will render. For timers, ensure it cleanly
-// divides 1000.
-const APP_TICK_RATE: u64 = 200;
+// divides 1000. Keep it small so lyrics change close to when they're sung.
+const APP_TICK_RATE: u64 = 50;
 const ENV_API_KEY: &str = "YOUTUBE_API_KEY";
+const ENV_LIBRARY_DIR: &str = "LIBRARY_DIR";
+const ENV_LYRICS_DIR: &str = "LYRICS_DIR";
+const ENV_LIVE_SEARCH: &str = "LIVE_SEARCH";
+const ENV_YOUTUBE_PAGE_SIZE: &str = "YOUTUBE_PAGE_SIZE";
+const ENV_YT_DLP: &str = "YT_DLP";
 
 #[tokio::main]
 async fn main() -> Result<()> {
   dotenv().ok();
+
+  // Create lyrics providers. Local .lrc files, from the lyrics directory and next to the tracks in
+  // the music library, are listed ahead of LRCLIB's.
+  let lyrics = LRCLib::new();
+  let lyrics_dirs = [ENV_LYRICS_DIR, ENV_LIBRARY_DIR]
+    .iter()
+    .filter_map(|key| dotenv::var(key).ok())
+    .map(PathBuf::from)
+    .collect();
+  let lyrics_fetcher = Layered::new(LocalLyrics::new(lyrics_dirs), lyrics.clone());
+  let player = Arc::new(Player::new()?);
+
+  // Search as you type when LIVE_SEARCH is on.
+  let live_search = dotenv::var(ENV_LIVE_SEARCH)
+    .is_ok_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+
+  let page_size = dotenv::var(ENV_YOUTUBE_PAGE_SIZE)
+    .ok()
+    .and_then(|size| size.parse().ok())
+    .unwrap_or(DEFAULT_PAGE_SIZE);
+
+  // yt-dlp finds the audio of YouTube videos, and searches YouTube when there's no API key.
+  let ytdlp_binary = dotenv::var(ENV_YT_DLP).unwrap_or_else(|_| ytdlp::DEFAULT_BINARY.to_string());
+
+  // A local music library takes precedence over YouTube, so the app can run offline. YouTube is
+  // searched through its API when there is a key, and through yt-dlp when there isn't.
+  match dotenv::var(ENV_LIBRARY_DIR) {
+    Ok(dir) => {
+      let audio = LocalLibrary::new(PathBuf::from(dir), player);
+
+      run(AppComponent::new(
+        Arc::new(lyrics_fetcher),
+        Arc::new(lyrics),
+        Arc::new(audio.clone()),
+        Arc::new(audio),
+        live_search,
+      ))
+      .await
+    }
+    Err(_) => match dotenv::var(ENV_API_KEY) {
+      Ok(api_key) => {
+        let audio = YouTube::new(api_key, ytdlp_binary, player, page_size);
+
+        run(AppComponent::new(
+          Arc::new(lyrics_fetcher),
+          Arc::new(lyrics),
+          Arc::new(audio.clone()),
+          Arc::new(audio),
+          live_search,
+        ))
+        .await
+      }
+      Err(_) => {
+        let audio = YtDlp::new(ytdlp_binary, player, page_size);
+
+        run(AppComponent::new(
+          Arc::new(lyrics_fetcher),
+          Arc::new(lyrics),
+          Arc::new(audio.clone()),
+          Arc::new(audio),
+          live_search,
+        ))
+        .await
+      }
+    },
+  }
+}
+
+async fn run<AF, AS, LF, LS>(mut app: AppComponent<'_, AF, AS, LF, LS>) -> Result<()>
+where
+  AF: AudioFetcher + Send + Sync + 'static,
+  AS: AudioService + Send + Sync + 'static,
+  LF: LyricsFetcher + Send + Sync + 'static,
+  LS: LyricsService + Send + Sync + 'static,
+{
   setup_terminal()?;
 
   let stdout = io::stdout();
@@ -43,18 +132,6 @@ async fn main() -> Result<()> {
   let mut terminal = Terminal::new(backend)?;
   let events = Events::new(APP_TICK_RATE);
 
-
-  let api_key = dotenv::var(ENV_API_KEY).expect("YOUTUBE_API_KEY must be set");
-  // Create lyrics provider.
-  let lyrics = LRCLib::new();
-  let audio = YouTube::new(api_key);
-
-  let mut app = AppComponent::new(
-    Arc::new(lyrics.clone()),
-    Arc::new(lyrics),
-    Arc::new(audio.clone()),
-    Arc::new(audio),
-  );
   terminal.clear()?;
 
   loop {
@@ -79,9 +156,14 @@ async fn main() -> Result<()> {
     }
   }
 
+  let saved = app.shutdown();
   shutdown_terminal()?;
   terminal.show_cursor()?;
 
+  if let Err(e) = saved {
+    eprintln!("Failed to save the session: {}", e);
+  }
+
   Ok(())
 }
 
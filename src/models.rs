@@ -1 +1,3 @@
-pub mod song;
\ No newline at end of file
+pub mod pairing;
+pub mod rotation;
+pub mod song;
@@ -0,0 +1,158 @@
+use crate::audio::AudioResult;
+use crate::lyrics::LyricsResult;
+use std::collections::HashSet;
+use std::time::Duration;
+
+// TITLE_WEIGHT is how much of the name similarity comes from the title; the rest is the artist.
+const TITLE_WEIGHT: f64 = 0.7;
+// NAME_WEIGHT is how much of the score comes from name similarity when both durations are known;
+// the rest is duration agreement.
+const NAME_WEIGHT: f64 = 0.6;
+// MAX_DURATION_GAP is how far apart two durations can be before they don't agree at all.
+const MAX_DURATION_GAP: Duration = Duration::from_secs(20);
+
+// Pairing is a suggested (audio, lyrics) pair, as indexes into the search results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pairing {
+    pub audio: usize,
+    pub lyrics: usize,
+    pub score: f64,
+}
+
+// score is our confidence, from 0 to 1, that the audio and lyrics are the same recording. It
+// combines how many of the lyrics' title and artist words appear in the audio's (audio titles are
+// often noisy, e.g. "Artist - Title (Karaoke Version)", so only the lyrics' words are counted)
+// with how close their durations are.
+pub fn score(audio: &AudioResult, lyrics: &LyricsResult) -> f64 {
+    let audio_words = words(&format!("{} {}", audio.title, audio.artist));
+
+    let title = coverage(&words(&lyrics.title), &audio_words);
+    let names = match words(&lyrics.artist) {
+        artist if artist.is_empty() => title,
+        artist => TITLE_WEIGHT * title + (1.0 - TITLE_WEIGHT) * coverage(&artist, &audio_words),
+    };
+
+    if audio.duration.is_zero() || lyrics.duration.is_zero() {
+        return names;
+    }
+
+    let gap = audio.duration.abs_diff(lyrics.duration).as_secs_f64();
+    let agreement = (1.0 - gap / MAX_DURATION_GAP.as_secs_f64()).max(0.0);
+
+    NAME_WEIGHT * names + (1.0 - NAME_WEIGHT) * agreement
+}
+
+// best_pair finds the highest scoring pair of audio and lyrics, if both lists have results.
+pub fn best_pair(audio: &[AudioResult], lyrics: &[LyricsResult]) -> Option<Pairing> {
+    audio
+        .iter()
+        .enumerate()
+        .flat_map(|(a, ar)| {
+            lyrics.iter().enumerate().map(move |(l, lr)| Pairing {
+                audio: a,
+                lyrics: l,
+                score: score(ar, lr),
+            })
+        })
+        .fold(None, |best: Option<Pairing>, pairing| match best {
+            Some(best) if best.score >= pairing.score => Some(best),
+            _ => Some(pairing),
+        })
+}
+
+// words splits text into its set of lowercased alphanumeric words.
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+// coverage is the fraction of needles found in haystack.
+fn coverage(needles: &HashSet<String>, haystack: &HashSet<String>) -> f64 {
+    if needles.is_empty() {
+        return 0.0;
+    }
+
+    needles.intersection(haystack).count() as f64 / needles.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lyrics::lyrics_result as lyrics;
+
+    fn audio(title: &str, secs: u64) -> AudioResult {
+        AudioResult {
+            id: title.to_string(),
+            title: title.to_string(),
+            artist: String::new(),
+            duration: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn scores_a_noisy_title_with_the_same_duration_as_certain() {
+        let ar = audio("Queen - Bohemian Rhapsody (Karaoke Version)", 354);
+        let lr = lyrics("Bohemian Rhapsody", "Queen", 354);
+
+        assert!((score(&ar, &lr) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scores_on_names_alone_without_both_durations() {
+        let lr = lyrics("Bohemian Rhapsody", "Queen", 354);
+
+        assert_eq!(score(&audio("Bohemian Rhapsody", 0), &lr), TITLE_WEIGHT);
+        let untimed = lyrics("Bohemian Rhapsody", "", 0);
+        assert_eq!(score(&audio("Bohemian Rhapsody", 354), &untimed), 1.0);
+    }
+
+    #[test]
+    fn scores_durations_apart_by_the_gap() {
+        let lr = lyrics("Bohemian Rhapsody", "", 354);
+
+        let close = score(&audio("Bohemian Rhapsody", 364), &lr);
+        let far = score(&audio("Bohemian Rhapsody", 400), &lr);
+
+        assert!((close - (NAME_WEIGHT + (1.0 - NAME_WEIGHT) * 0.5)).abs() < 1e-9);
+        assert!((far - NAME_WEIGHT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scores_nothing_for_untitled_lyrics() {
+        assert_eq!(score(&audio("Bohemian Rhapsody", 0), &lyrics("", "", 0)), 0.0);
+    }
+
+    #[test]
+    fn finds_no_pair_without_results() {
+        let lr = [lyrics("Bohemian Rhapsody", "Queen", 354)];
+        let ar = [audio("Bohemian Rhapsody", 354)];
+
+        assert_eq!(best_pair(&[], &lr), None);
+        assert_eq!(best_pair(&ar, &[]), None);
+    }
+
+    #[test]
+    fn pairs_the_best_match() {
+        let ar = [audio("Don't Stop Me Now", 209), audio("Bohemian Rhapsody", 354)];
+        let lr = [
+            lyrics("Bohemian Rhapsody", "", 300),
+            lyrics("Bohemian Rhapsody", "", 355),
+        ];
+
+        let best = best_pair(&ar, &lr).unwrap();
+
+        assert_eq!((best.audio, best.lyrics), (1, 1));
+    }
+
+    #[test]
+    fn keeps_the_first_pair_on_a_tie() {
+        let ar = [audio("Bohemian Rhapsody", 0), audio("Bohemian Rhapsody", 0)];
+        let lr = [lyrics("Bohemian Rhapsody", "", 0), lyrics("Bohemian Rhapsody", "", 0)];
+
+        let best = best_pair(&ar, &lr).unwrap();
+
+        assert_eq!((best.audio, best.lyrics, best.score), (0, 0, 1.0));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::models::song::{Song, SongList};
+
+// rotate orders the queue for a fair singer rotation: every singer gets one song per round, and
+// the singers who sang longest ago (or haven't sung yet) go first. Each singer's own songs keep the
+// order they were queued in. sung lists the songs already sung, oldest first.
+//
+// Singers are matched ignoring case and surrounding spaces. Songs without a singer share a turn,
+// as if they were one singer's.
+pub fn rotate(queue: &[Song], sung: &[&Song]) -> SongList {
+    // Group the queue by singer, in the order each singer first appears.
+    let mut singers: Vec<(String, Vec<&Song>)> = Vec::new();
+    for song in queue {
+        let key = singer_key(&song.singer);
+        match singers.iter_mut().find(|(singer, _)| *singer == key) {
+            Some((_, songs)) => songs.push(song),
+            None => singers.push((key, vec![song])),
+        }
+    }
+
+    // Singers who haven't sung sort first (None), then by how long ago they last sang. The sort is
+    // stable, so ties keep their order in the queue.
+    singers.sort_by_key(|(singer, _)| {
+        sung.iter()
+            .rposition(|song| singer_key(&song.singer) == *singer)
+    });
+
+    let rounds = singers
+        .iter()
+        .map(|(_, songs)| songs.len())
+        .max()
+        .unwrap_or_default();
+
+    (0..rounds)
+        .flat_map(|round| {
+            singers
+                .iter()
+                .filter_map(move |(_, songs)| songs.get(round).copied())
+        })
+        .cloned()
+        .collect()
+}
+
+fn singer_key(singer: &str) -> String {
+    singer.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(singer: &str, title: &str) -> Song {
+        let mut song = Song::new();
+        song.singer = singer.to_string();
+        song.title = title.to_string();
+        song
+    }
+
+    fn titles(songs: &[Song]) -> Vec<&str> {
+        songs.iter().map(|song| song.title.as_str()).collect()
+    }
+
+    #[test]
+    fn gives_every_singer_one_song_per_round() {
+        let queue = [
+            song("Ana", "a1"),
+            song("Ana", "a2"),
+            song("Ana", "a3"),
+            song("Ben", "b1"),
+            song("Cat", "c1"),
+            song("Ben", "b2"),
+        ];
+
+        let rotated = rotate(&queue, &[]);
+
+        assert_eq!(titles(&rotated), ["a1", "b1", "c1", "a2", "b2", "a3"]);
+    }
+
+    #[test]
+    fn puts_new_singers_first() {
+        let sung = song("Ana", "earlier");
+        let queue = [song("Ana", "a1"), song("Ben", "b1")];
+
+        let rotated = rotate(&queue, &[&sung]);
+
+        assert_eq!(titles(&rotated), ["b1", "a1"]);
+    }
+
+    #[test]
+    fn puts_repeat_singers_in_order_of_their_last_song() {
+        let history = [song("Ben", "b0"), song("Ana", "a0"), song("Ben", "b00")];
+        let sung: Vec<&Song> = history.iter().collect();
+        let queue = [song("Ben", "b1"), song("Ana", "a1"), song("Cat", "c1")];
+
+        let rotated = rotate(&queue, &sung);
+
+        assert_eq!(titles(&rotated), ["c1", "a1", "b1"]);
+    }
+
+    #[test]
+    fn groups_unnamed_singers_and_ignores_case() {
+        let queue = [
+            song("", "u1"),
+            song(" ana ", "a1"),
+            song("", "u2"),
+            song("ANA", "a2"),
+        ];
+
+        let rotated = rotate(&queue, &[]);
+
+        assert_eq!(titles(&rotated), ["u1", "a1", "u2", "a2"]);
+    }
+
+    #[test]
+    fn keeps_a_rotated_queue_as_it_is() {
+        let queue = [
+            song("Ana", "a1"),
+            song("Ben", "b1"),
+            song("Ana", "a2"),
+            song("Ben", "b2"),
+        ];
+
+        let rotated = rotate(&queue, &[]);
+
+        assert_eq!(rotate(&rotated, &[]), rotated);
+        assert!(rotate(&[], &[]).is_empty());
+    }
+}
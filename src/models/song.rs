@@ -1,14 +1,18 @@
 use crate::audio::AudioResult;
 use crate::lyrics::LyricsResult;
-use crate::util::deserialize_u64;
+use crate::util::{deserialize_u64, serialize_u64};
 use std::collections::BTreeMap;
 use std::time::Duration;
 
 // Song is the master struct that holds information composed by both lyric and audio sources.
-#[derive(Debug, Clone, serde::Deserialize, Default, PartialEq)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Song {
-    #[serde(deserialize_with = "deserialize_u64", rename = "id")]
+    #[serde(
+        deserialize_with = "deserialize_u64",
+        serialize_with = "serialize_u64",
+        rename = "id"
+    )]
     pub(crate) lyric_id: String,
     pub(crate) video_id: String,
     #[serde(rename = "track_name")]
@@ -18,7 +22,12 @@ pub struct Song {
     duration: Duration,
     pub(crate) duration_ms: u64,
     pub(crate) synced_lyrics: String,
+    #[serde(default)]
+    pub(crate) plain_lyrics: String,
     pub(crate) lyric_map: Option<LyricsMap>,
+    // singer is the name of whoever queued the song to sing it.
+    #[serde(default)]
+    pub(crate) singer: String,
     pub message: (),
 }
 
@@ -30,7 +39,9 @@ impl Song {
             title: "".to_string(),
             artist: "".to_string(),
             synced_lyrics: "".to_string(),
+            plain_lyrics: "".to_string(),
             lyric_map: None,
+            singer: "".to_string(),
             duration: Duration::new(0, 0),
             duration_ms: 0,
             message: (),
@@ -48,7 +59,9 @@ impl Song {
             duration: self.duration.clone(),
             duration_ms: self.duration_ms.clone(),
             synced_lyrics: lr.synced_lyrics.clone(),
+            plain_lyrics: lr.plain_lyrics.clone(),
             lyric_map: map,
+            singer: self.singer.clone(),
             message: (),
         }
     }
@@ -62,7 +75,9 @@ impl Song {
             duration: ar.duration,
             duration_ms: ar.duration.as_millis() as u64,
             synced_lyrics: self.synced_lyrics.clone(),
+            plain_lyrics: self.plain_lyrics.clone(),
             lyric_map: self.lyric_map.clone(),
+            singer: self.singer.clone(),
             message: (),
         }
     }
@@ -70,4 +85,27 @@ impl Song {
 
 pub type SongList = Vec<Song>;
 
-pub type LyricsMap = BTreeMap<u64, String>;
+pub type LyricsMap = BTreeMap<u64, LyricLine>;
+
+// LyricLine is one line of synced lyrics, along with its word timings when the lyrics have them.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq)]
+pub struct LyricLine {
+    pub(crate) text: String,
+    pub(crate) words: Vec<LyricWord>,
+}
+
+// LyricWord is a word of a line, sung from start_ms until the next word starts.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq)]
+pub struct LyricWord {
+    pub(crate) start_ms: u64,
+    pub(crate) text: String,
+}
+
+impl LyricLine {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            words: Vec::new(),
+        }
+    }
+}
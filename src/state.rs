@@ -1,4 +1,6 @@
+use crate::models::rotation;
 use crate::models::song::{Song, SongList};
+use crate::util::EMDASH;
 use crate::state::SongState::Paused;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
@@ -16,8 +18,13 @@ pub enum Focus {
     Help,
     #[default]
     Home,
+    // Playlist is the prompt for importing or exporting a playlist, opened from the queue.
+    Playlist,
     Queue,
+    // Restore is the offer to restore the last session, made on startup.
+    Restore,
     Search,
+    Seek,
     Timer,
 }
 
@@ -31,14 +38,26 @@ pub enum SongState {
 
 #[derive(Default, Clone, PartialEq, Debug)]
 pub struct GlobalState {
-    // current_lyrics will be a set of 3 lines of lyrics, where index 1 is the current lyric.
-    pub(crate) current_lyrics: Vec<String>,
     pub(crate) current_song: Option<Song>,
     pub(crate) current_song_elapsed_ms: u64,
     pub(crate) focus: Focus,
+    // history holds the songs that have been played, most recent last.
+    pub(crate) history: SongList,
+    // lyric_offset_ms shifts the lyrics against the audio. Positive values show lyrics earlier.
+    pub(crate) lyric_offset_ms: i64,
     pub(crate) mode: InputMode,
+    // playback_id identifies the current playback of a song. It changes whenever playback is
+    // started or cut short, so an audio thread that outlives its song can tell.
+    pub(crate) playback_id: u64,
+    // rotation is whether the queue takes turns between singers rather than playing songs in the
+    // order they were added. Every song queued while it's on reorders the whole queue, so moving a
+    // song by hand turns it off.
+    pub(crate) rotation: bool,
     pub(crate) session_time_elapsed: Duration,
     pub(crate) song_list: SongList,
+    // status tells the user about something that went wrong in the background, e.g. that the
+    // session couldn't be saved. The footer shows it until it's cleared.
+    pub(crate) status: Option<String>,
     pub(crate) song_state: SongState,
 }
 
@@ -52,17 +71,68 @@ impl GlobalState {
             song_state: SongState::None,
             current_song: None,
             current_song_elapsed_ms: 0,
-            current_lyrics: Vec::new(),
+            history: Vec::new(),
+            lyric_offset_ms: 0,
+            playback_id: 0,
+            rotation: false,
             song_list: Vec::new(),
             mode: InputMode::Nav,
             focus: Focus::Home,
             session_time_elapsed: Duration::new(0, 0),
+            status: None,
         }
     }
 
     pub fn has_next_song(&self) -> bool {
         self.current_song.is_none() && !self.song_list.is_empty()
     }
+
+    // end_song moves the current song, if any, to the history, leaving nothing playing.
+    pub fn end_song(&mut self) {
+        if let Some(song) = self.current_song.take() {
+            self.history.push(song);
+        }
+
+        self.song_state = SongState::None;
+        self.current_song_elapsed_ms = 0;
+        self.lyric_offset_ms = 0;
+    }
+
+    // enqueue adds a song to the queue, taking its turn in the rotation if that's on.
+    pub fn enqueue(&mut self, song: Song) {
+        self.song_list.push(song);
+        self.rotate_queue();
+    }
+
+    // rotate_queue reorders the queue into the singer rotation, if it's on.
+    pub fn rotate_queue(&mut self) {
+        if !self.rotation {
+            return;
+        }
+
+        let sung: Vec<&Song> = self.history.iter().chain(&self.current_song).collect();
+        self.song_list = rotation::rotate(&self.song_list, &sung);
+    }
+
+    // set_rotation turns the singer rotation on or off. Turning it off leaves the queue as it is.
+    pub fn set_rotation(&mut self, rotation: bool) {
+        self.rotation = rotation;
+        self.rotate_queue();
+    }
+
+    // up_next describes the next song in the queue and who's singing it.
+    pub fn up_next(&self) -> Option<String> {
+        self.song_list.first().map(|song| match song.singer.is_empty() {
+            true => format!("Up next: {}", song.title),
+            false => format!("Up next: {} {} {}", song.singer, EMDASH, song.title),
+        })
+    }
+
+    // lyrics_elapsed_ms is the point in the song the lyrics should show, after the lyric offset.
+    pub fn lyrics_elapsed_ms(&self) -> u64 {
+        self.current_song_elapsed_ms
+            .saturating_add_signed(self.lyric_offset_ms)
+    }
 }
 
 // This is a global state that will be shared across the application.
@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+pub(crate) mod offsets;
+pub(crate) mod playlist;
+pub(crate) mod session;
+
+const APP_DIR: &str = "cliraoke";
+
+// data_dir is where CLIraoke keeps its files: $XDG_DATA_HOME/cliraoke, falling back to
+// ~/.local/share/cliraoke.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local").join("share"),
+    };
+
+    Some(base.join(APP_DIR))
+}
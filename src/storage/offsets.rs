@@ -0,0 +1,104 @@
+use crate::storage::data_dir;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const OFFSETS_FILE: &str = "offsets.json";
+
+// OffsetStore remembers the lyric offset chosen for each pairing of audio and lyrics, so a fix
+// made mid-song sticks the next time the same pair is queued.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OffsetStore {
+    path: Option<PathBuf>,
+    offsets: HashMap<String, i64>,
+}
+
+impl OffsetStore {
+    // load reads the saved offsets. A missing or unreadable file just means no offsets yet.
+    pub(crate) fn load() -> Self {
+        Self::open(data_dir().map(|dir| dir.join(OFFSETS_FILE)))
+    }
+
+    fn open(path: Option<PathBuf>) -> Self {
+        let offsets = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self { path, offsets }
+    }
+
+    pub(crate) fn get(&self, video_id: &str, lyric_id: &str) -> i64 {
+        self.offsets
+            .get(&key(video_id, lyric_id))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    // set records the offset for a pairing and saves it straight away.
+    pub(crate) fn set(
+        &mut self,
+        video_id: &str,
+        lyric_id: &str,
+        offset_ms: i64,
+    ) -> anyhow::Result<()> {
+        match offset_ms {
+            0 => self.offsets.remove(&key(video_id, lyric_id)),
+            _ => self.offsets.insert(key(video_id, lyric_id), offset_ms),
+        };
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.offsets)?)?;
+
+        Ok(())
+    }
+}
+
+fn key(video_id: &str, lyric_id: &str) -> String {
+    format!("{}|{}", video_id, lyric_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_offsets_per_pairing() {
+        let mut store = OffsetStore::open(None);
+
+        store.set("dQw4w9WgXcQ", "12345", 300).unwrap();
+
+        assert_eq!(store.get("dQw4w9WgXcQ", "12345"), 300);
+        assert_eq!(store.get("dQw4w9WgXcQ", "67890"), 0);
+        assert_eq!(store.get("oRdxUFDoQe0", "12345"), 0);
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let dir = std::env::temp_dir().join(format!("cliraoke-offsets-{}", std::process::id()));
+        let path = dir.join(OFFSETS_FILE);
+
+        let mut store = OffsetStore::open(Some(path.clone()));
+        store.set("dQw4w9WgXcQ", "12345", 300).unwrap();
+        store.set("Queen/Bohemian Rhapsody.mp3", "Queen/Bohemian Rhapsody.lrc", -1_200).unwrap();
+        store.set("oRdxUFDoQe0", "67890", 500).unwrap();
+        // Setting an offset back to zero forgets it.
+        store.set("oRdxUFDoQe0", "67890", 0).unwrap();
+
+        let loaded = OffsetStore::open(Some(path));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.get("dQw4w9WgXcQ", "12345"), 300);
+        assert_eq!(
+            loaded.get("Queen/Bohemian Rhapsody.mp3", "Queen/Bohemian Rhapsody.lrc"),
+            -1_200
+        );
+        assert_eq!(loaded.get("oRdxUFDoQe0", "67890"), 0);
+        assert_eq!(loaded.offsets.len(), 2);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::models::song::Song;
+use crate::util::{deserialize_u64, serialize_u64};
+use anyhow::anyhow;
+use std::path::Path;
+
+const M3U_HEADER: &str = "#EXTM3U";
+const M3U_INFO: &str = "#EXTINF:";
+// M3U_LYRICS is our own M3U directive, carrying the lyrics id of the entry that follows it. Other
+// players skip it like any other comment.
+const M3U_LYRICS: &str = "#EXTLYRICS:";
+const CSV_COLUMNS: [&str; 5] = ["video_id", "lyric_id", "title", "artist", "duration"];
+const YOUTUBE_WATCH_URL: &str = "https://www.youtube.com/watch?v=";
+
+// Format is a playlist file format, picked by the file's extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    Json,
+    M3u,
+    Csv,
+}
+
+impl Format {
+    pub(crate) fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "json" => Ok(Format::Json),
+            "m3u" | "m3u8" => Ok(Format::M3u),
+            "csv" => Ok(Format::Csv),
+            _ => Err(anyhow!(
+                "Unknown playlist format for {}; use .json, .m3u or .csv",
+                path.display()
+            )),
+        }
+    }
+}
+
+// PlaylistEntry is one song of a playlist: which audio to play, and which lyrics to show with it.
+// The title, artist and duration are kept so the lyrics can be found again by name if their id
+// can't be.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PlaylistEntry {
+    pub(crate) video_id: String,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_u64",
+        serialize_with = "serialize_u64"
+    )]
+    pub(crate) lyric_id: String,
+    #[serde(default)]
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) artist: String,
+    // duration_secs is the length of the audio, or zero if unknown.
+    #[serde(default)]
+    pub(crate) duration_secs: u64,
+}
+
+impl From<&Song> for PlaylistEntry {
+    fn from(song: &Song) -> Self {
+        Self {
+            video_id: song.video_id.clone(),
+            lyric_id: song.lyric_id.clone(),
+            title: song.title.clone(),
+            artist: song.artist.clone(),
+            duration_secs: song.duration_ms / 1000,
+        }
+    }
+}
+
+// export writes songs to a playlist at path, in the format its extension asks for.
+pub(crate) fn export(path: &Path, songs: &[Song]) -> anyhow::Result<()> {
+    let entries: Vec<PlaylistEntry> = songs.iter().map(PlaylistEntry::from).collect();
+
+    let contents = match Format::from_path(path)? {
+        Format::Json => serde_json::to_string_pretty(&entries)?,
+        Format::M3u => to_m3u(&entries),
+        Format::Csv => to_csv(&entries),
+    };
+
+    std::fs::write(path, contents)
+        .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+// import reads the entries of the playlist at path.
+pub(crate) fn import(path: &Path) -> anyhow::Result<Vec<PlaylistEntry>> {
+    let format = Format::from_path(path)?;
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+
+    match format {
+        Format::Json => Ok(serde_json::from_str(&contents)?),
+        Format::M3u => Ok(from_m3u(&contents)),
+        Format::Csv => from_csv(&contents),
+    }
+}
+
+// to_m3u writes an extended M3U playlist. YouTube videos are listed by their watch URL, so the
+// playlist also works in other players, and local tracks by their path in the library.
+fn to_m3u(entries: &[PlaylistEntry]) -> String {
+    let mut m3u = format!("{}\n", M3U_HEADER);
+
+    for entry in entries {
+        let duration = match entry.duration_secs {
+            0 => "-1".to_string(),
+            secs => secs.to_string(),
+        };
+        m3u.push_str(&format!(
+            "{}{},{} - {}\n",
+            M3U_INFO, duration, entry.artist, entry.title
+        ));
+        if !entry.lyric_id.is_empty() {
+            m3u.push_str(&format!("{}{}\n", M3U_LYRICS, entry.lyric_id));
+        }
+
+        match is_youtube_id(&entry.video_id) {
+            true => m3u.push_str(&format!("{}{}\n", YOUTUBE_WATCH_URL, entry.video_id)),
+            false => m3u.push_str(&format!("{}\n", entry.video_id)),
+        }
+    }
+
+    m3u
+}
+
+// from_m3u reads an M3U playlist. Directives describe the location that follows them, and anything
+// else starting with # is a comment.
+fn from_m3u(contents: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut entry = PlaylistEntry::default();
+
+    for line in contents.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(info) = line.strip_prefix(M3U_INFO) {
+            // #EXTINF:<seconds>,<artist> - <title>
+            let (duration, name) = info.split_once(',').unwrap_or((info, ""));
+            entry.duration_secs = duration.trim().parse().unwrap_or_default();
+            (entry.artist, entry.title) = match name.split_once(" - ") {
+                Some((artist, title)) => (artist.trim().to_string(), title.trim().to_string()),
+                None => (String::new(), name.trim().to_string()),
+            };
+        } else if let Some(lyric_id) = line.strip_prefix(M3U_LYRICS) {
+            entry.lyric_id = lyric_id.trim().to_string();
+        } else if !line.starts_with('#') {
+            entry.video_id = video_id(line);
+            entries.push(std::mem::take(&mut entry));
+        }
+    }
+
+    entries
+}
+
+// to_csv writes a CSV playlist with a header row.
+fn to_csv(entries: &[PlaylistEntry]) -> String {
+    let mut csv = format!("{}\n", CSV_COLUMNS.join(","));
+
+    for entry in entries {
+        let duration = entry.duration_secs.to_string();
+        let fields = [
+            &entry.video_id,
+            &entry.lyric_id,
+            &entry.title,
+            &entry.artist,
+            &duration,
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&format!("{}\n", row.join(",")));
+    }
+
+    csv
+}
+
+// from_csv reads a CSV playlist. Its header row names the columns, which can come in any order;
+// only video_id is required.
+fn from_csv(contents: &str) -> anyhow::Result<Vec<PlaylistEntry>> {
+    let mut rows = csv_rows(contents).into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .unwrap_or_default()
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let video =
+        column("video_id").ok_or_else(|| anyhow!("The playlist has no video_id column"))?;
+    let (lyric_id, title, artist, duration) = (
+        column("lyric_id"),
+        column("title"),
+        column("artist"),
+        column("duration"),
+    );
+
+    let entries = rows
+        .filter(|row| row.iter().any(|field| !field.trim().is_empty()))
+        .map(|row| {
+            let field = |index: Option<usize>| {
+                index
+                    .and_then(|index| row.get(index))
+                    .map(|field| field.trim().to_string())
+                    .unwrap_or_default()
+            };
+
+            PlaylistEntry {
+                video_id: video_id(&field(Some(video))),
+                lyric_id: field(lyric_id),
+                title: field(title),
+                artist: field(artist),
+                duration_secs: field(duration).parse().unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// csv_field quotes a field if it has to be.
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+// csv_rows splits CSV into rows of fields. Quoted fields can hold commas, newlines and doubled
+// quotes.
+fn csv_rows(contents: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows
+}
+
+// video_id takes the video id out of a YouTube URL. Anything else, like a path in the local
+// library, is an id already.
+fn video_id(location: &str) -> String {
+    let youtube_id = location
+        .split_once("youtube.com/watch?")
+        .and_then(|(_, query)| {
+            query
+                .split('&')
+                .find_map(|param| param.strip_prefix("v="))
+        })
+        .or_else(|| location.split_once("youtu.be/").map(|(_, rest)| rest))
+        .map(|id| id.split(['?', '&', '#']).next().unwrap_or(id));
+
+    youtube_id.unwrap_or(location).to_string()
+}
+
+// is_youtube_id is whether id looks like a YouTube video id: 11 letters, digits, - and _.
+fn is_youtube_id(id: &str) -> bool {
+    id.len() == 11
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<PlaylistEntry> {
+        vec![
+            PlaylistEntry {
+                video_id: "dQw4w9WgXcQ".to_string(),
+                lyric_id: "12345".to_string(),
+                title: "Never Gonna Give You Up".to_string(),
+                artist: "Rick Astley".to_string(),
+                duration_secs: 213,
+            },
+            PlaylistEntry {
+                video_id: "Queen/Bohemian Rhapsody.mp3".to_string(),
+                lyric_id: String::new(),
+                title: "Bohemian Rhapsody, Live".to_string(),
+                artist: "Queen".to_string(),
+                duration_secs: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn picks_the_format_by_extension() {
+        assert_eq!(Format::from_path(Path::new("set.JSON")).unwrap(), Format::Json);
+        assert_eq!(Format::from_path(Path::new("set.m3u8")).unwrap(), Format::M3u);
+        assert_eq!(Format::from_path(Path::new("set.csv")).unwrap(), Format::Csv);
+        assert!(Format::from_path(Path::new("set.txt")).is_err());
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let json = serde_json::to_string(&entries()).unwrap();
+
+        assert!(json.contains(r#""lyricId":12345"#));
+        assert_eq!(serde_json::from_str::<Vec<PlaylistEntry>>(&json).unwrap(), entries());
+    }
+
+    #[test]
+    fn round_trips_m3u() {
+        let m3u = to_m3u(&entries());
+
+        assert!(m3u.starts_with("#EXTM3U\n"));
+        assert!(m3u.contains("https://www.youtube.com/watch?v=dQw4w9WgXcQ\n"));
+        assert_eq!(from_m3u(&m3u), entries());
+    }
+
+    #[test]
+    fn reads_plain_m3u() {
+        let m3u = "https://youtu.be/dQw4w9WgXcQ?t=10\n# a comment\nhttps://www.youtube.com/watch?list=x&v=abcdefghijk\n";
+
+        let ids: Vec<String> = from_m3u(m3u).into_iter().map(|e| e.video_id).collect();
+        assert_eq!(ids, ["dQw4w9WgXcQ", "abcdefghijk"]);
+    }
+
+    #[test]
+    fn round_trips_csv() {
+        let csv = to_csv(&entries());
+
+        assert!(csv.contains(r#""Bohemian Rhapsody, Live""#));
+        assert_eq!(from_csv(&csv).unwrap(), entries());
+    }
+
+    #[test]
+    fn reads_csv_columns_in_any_order() {
+        let csv = "Title,Video_ID\r\n\"Say \"\"Hi\"\"\",abcdefghijk\r\n\r\n";
+
+        let entries = from_csv(csv).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].video_id, "abcdefghijk");
+        assert_eq!(entries[0].title, "Say \"Hi\"");
+        assert!(entries[0].lyric_id.is_empty());
+    }
+
+    #[test]
+    fn requires_a_video_id_column() {
+        assert!(from_csv("title,artist\nSong,Someone\n").is_err());
+    }
+}
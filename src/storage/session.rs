@@ -0,0 +1,306 @@
+use crate::models::song::{Song, SongList};
+use crate::state::GlobalState;
+use crate::storage::data_dir;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+const SESSION_FILE: &str = "session.json";
+// POSITION_INTERVAL is how often the position in the playing song is saved. Changes to the queue
+// or the current song are saved straight away.
+const POSITION_INTERVAL: Duration = Duration::from_secs(10);
+
+// Session is what's kept of the app's state between runs: the queue, and the song that was playing
+// and how far into it it got.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Session {
+    pub(crate) current_song: Option<Song>,
+    pub(crate) current_song_elapsed_ms: u64,
+    pub(crate) song_list: SongList,
+}
+
+impl Session {
+    // from_state takes the session from the app's state. The position is kept to the second, which
+    // is plenty to resume from.
+    pub(crate) fn from_state(state: &GlobalState) -> Self {
+        Self {
+            current_song: state.current_song.clone(),
+            current_song_elapsed_ms: position_ms(state),
+            song_list: state.song_list.clone(),
+        }
+    }
+
+    // has_songs_of is whether the session holds the same current song and queue as state. Songs are
+    // compared by their ids and singer only, so it's cheap enough to check on every tick.
+    fn has_songs_of(&self, state: &GlobalState) -> bool {
+        same_songs(self.current_song.as_slice(), state.current_song.as_slice())
+            && same_songs(&self.song_list, &state.song_list)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.current_song.is_none() && self.song_list.is_empty()
+    }
+}
+
+// position_ms is how far into the current song the session is, to the second.
+fn position_ms(state: &GlobalState) -> u64 {
+    match state.current_song {
+        Some(_) => state.current_song_elapsed_ms / 1000 * 1000,
+        None => 0,
+    }
+}
+
+fn same_songs(a: &[Song], b: &[Song]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.video_id == b.video_id && a.lyric_id == b.lyric_id && a.singer == b.singer
+        })
+}
+
+// SessionStore saves the session as it changes, so the queue survives the app quitting or
+// crashing. Sessions are written in the background, off the UI thread.
+#[derive(Debug)]
+pub(crate) struct SessionStore {
+    path: Option<PathBuf>,
+    // saved is the session last handed over to be written, at saved_at.
+    saved: Session,
+    saved_at: Instant,
+    // written is the number of the newest session on disk, so a write that finishes late can't
+    // replace a newer session with an older one. Holding its lock keeps writes from overlapping.
+    written: Arc<Mutex<u64>>,
+    writes: u64,
+    // error is why the last write failed, until one succeeds.
+    error: Option<String>,
+    results_rx: UnboundedReceiver<anyhow::Result<()>>,
+    results_tx: UnboundedSender<anyhow::Result<()>>,
+}
+
+impl SessionStore {
+    // load reads the session the last run left behind. A missing or unreadable file just means
+    // there's nothing to restore.
+    pub(crate) fn load() -> (Self, Session) {
+        Self::open(data_dir().map(|dir| dir.join(SESSION_FILE)))
+    }
+
+    fn open(path: Option<PathBuf>) -> (Self, Session) {
+        let saved: Session = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let (results_tx, results_rx) = unbounded_channel();
+
+        (
+            Self {
+                path,
+                saved: saved.clone(),
+                saved_at: Instant::now(),
+                written: Arc::new(Mutex::new(0)),
+                writes: 0,
+                error: None,
+                results_rx,
+                results_tx,
+            },
+            saved,
+        )
+    }
+
+    // snapshot takes the session from state if it needs saving: when the queue or the current song
+    // has changed, or when the position has and it was last saved POSITION_INTERVAL ago. Forcing it
+    // saves a changed position straight away, e.g. on pause.
+    pub(crate) fn snapshot(
+        &mut self,
+        state: &GlobalState,
+        force: bool,
+        now: Instant,
+    ) -> Option<Session> {
+        let moved = self.saved.current_song_elapsed_ms != position_ms(state);
+        let due = force || now.duration_since(self.saved_at) >= POSITION_INTERVAL;
+        if self.saved.has_songs_of(state) && !(moved && due) {
+            return None;
+        }
+
+        self.saved = Session::from_state(state);
+        self.saved_at = now;
+        Some(self.saved.clone())
+    }
+
+    // save writes session in the background. poll reports how it went.
+    pub(crate) fn save(&mut self, session: Session) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+
+        self.writes += 1;
+        let (written, number) = (self.written.clone(), self.writes);
+        let tx = self.results_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let _ = tx.send(write(&path, &written, number, &session));
+        });
+    }
+
+    // save_now writes session before returning, for when the app is about to exit.
+    pub(crate) fn save_now(&mut self, session: Session) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        self.writes += 1;
+        write(path, &self.written, self.writes, &session)
+    }
+
+    // poll picks up the writes that have finished, and returns whether that changed error.
+    pub(crate) fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(result) = self.results_rx.try_recv() {
+            let error = result.err().map(|e| e.to_string());
+            changed |= error != self.error;
+            self.error = error;
+        }
+
+        changed
+    }
+
+    pub(crate) fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+// write writes the numbered session to path, unless a newer one has been written already. A failed
+// write isn't retried until the session changes again.
+fn write(path: &Path, written: &Mutex<u64>, number: u64, session: &Session) -> anyhow::Result<()> {
+    let mut written = written.lock().expect("Failed to lock session file");
+    if *written > number {
+        return Ok(());
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    // Write the new session beside the old one and swap it in, so a crash part way through can't
+    // leave a half-written session behind.
+    let partial = path.with_extension("json.partial");
+    std::fs::write(&partial, serde_json::to_string(session)?)?;
+    std::fs::rename(&partial, path)?;
+
+    *written = number;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song(video_id: &str, lyric_id: &str) -> Song {
+        let mut song = Song::new();
+        song.video_id = video_id.to_string();
+        song.lyric_id = lyric_id.to_string();
+        song
+    }
+
+    fn state() -> GlobalState {
+        let mut state = GlobalState::new();
+        state.current_song = Some(song("dQw4w9WgXcQ", "12345"));
+        state.current_song_elapsed_ms = 61_500;
+        state.song_list = vec![song("Queen/Bohemian Rhapsody.mp3", "Queen/Bohemian Rhapsody.lrc")];
+        state
+    }
+
+    // store opens a store that has already saved state, at the returned time.
+    fn store() -> (SessionStore, Instant) {
+        let (mut store, _) = SessionStore::open(None);
+        let now = Instant::now();
+        assert!(store.snapshot(&state(), false, now).is_some());
+
+        (store, now)
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let session = Session::from_state(&state());
+
+        let json = serde_json::to_string(&session).unwrap();
+
+        assert!(json.contains(r#""id":12345"#));
+        assert!(json.contains(r#""id":"Queen/Bohemian Rhapsody.lrc""#));
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+    }
+
+    #[test]
+    fn keeps_the_position_to_the_second() {
+        let mut state = state();
+        assert_eq!(Session::from_state(&state).current_song_elapsed_ms, 61_000);
+
+        state.current_song = None;
+        assert_eq!(Session::from_state(&state).current_song_elapsed_ms, 0);
+    }
+
+    #[test]
+    fn saves_song_changes_straight_away() {
+        let (mut store, now) = store();
+        assert_eq!(store.snapshot(&state(), false, now), None);
+
+        let mut changed = state();
+        changed.song_list.push(song("oRdxUFDoQe0", ""));
+        assert!(store.snapshot(&changed, false, now).is_some());
+
+        let mut changed = state();
+        changed.song_list[0].singer = "Ana".to_string();
+        assert!(store.snapshot(&changed, false, now).is_some());
+    }
+
+    #[test]
+    fn saves_the_position_every_interval() {
+        let (mut store, now) = store();
+        let mut state = state();
+        state.current_song_elapsed_ms = 65_000;
+
+        assert_eq!(store.snapshot(&state, false, now + Duration::from_secs(4)), None);
+
+        let later = now + POSITION_INTERVAL;
+        let session = store.snapshot(&state, false, later).unwrap();
+        assert_eq!(session.current_song_elapsed_ms, 65_000);
+        assert_eq!(store.snapshot(&state, false, later + POSITION_INTERVAL), None);
+    }
+
+    #[test]
+    fn saves_the_position_when_forced() {
+        let (mut store, now) = store();
+        assert_eq!(store.snapshot(&state(), true, now), None);
+
+        let mut state = state();
+        state.current_song_elapsed_ms = 62_000;
+        assert!(store.snapshot(&state, true, now).is_some());
+    }
+
+    #[test]
+    fn writes_the_session_to_disk() {
+        let dir = std::env::temp_dir().join(format!("cliraoke-session-{}", std::process::id()));
+        let path = dir.join(SESSION_FILE);
+
+        let (mut store, _) = SessionStore::open(Some(path.clone()));
+        let session = Session::from_state(&state());
+        store.save_now(session.clone()).unwrap();
+        let (_, loaded) = SessionStore::open(Some(path));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded, session);
+    }
+
+    #[test]
+    fn skips_a_write_older_than_the_one_on_disk() {
+        let dir = std::env::temp_dir().join(format!("cliraoke-stale-{}", std::process::id()));
+        let path = dir.join(SESSION_FILE);
+        let written = Mutex::new(0);
+
+        let newer = Session::from_state(&state());
+        write(&path, &written, 2, &newer).unwrap();
+        write(&path, &written, 1, &Session::default()).unwrap();
+        let (_, loaded) = SessionStore::open(Some(path));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded, newer);
+    }
+}
@@ -1,13 +1,82 @@
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serializer};
 use serde_json::Number;
 
+// deserialize_u64 reads an id that may be a JSON number, like LRCLIB's, or a string, like the path
+// of a local lyrics file.
 pub fn deserialize_u64<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s = Number::deserialize(deserializer)?;
-    Ok(s.to_string())
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        Number(Number),
+        String(String),
+    }
+
+    match Id::deserialize(deserializer)? {
+        Id::Number(n) => Ok(n.to_string()),
+        Id::String(s) => Ok(s),
+    }
+}
+
+// serialize_u64 is the counterpart of deserialize_u64: ids that are numbers are written as numbers,
+// and anything else as a string. Ids that only read as numbers, like "007", stay strings, so they
+// come back unchanged.
+pub fn serialize_u64<S>(id: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match id.parse::<u64>() {
+        Ok(n) if n.to_string() == id => serializer.serialize_u64(n),
+        _ => serializer.serialize_str(id),
+    }
 }
 
 pub const EMOJI_MARTINI: char = '\u{1F378}';
 pub const EMDASH: char = '\u{2014}';
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Record {
+        #[serde(deserialize_with = "deserialize_u64", serialize_with = "serialize_u64")]
+        id: String,
+    }
+
+    fn round_trip(id: &str) -> (String, String) {
+        let json = serde_json::to_string(&Record { id: id.to_string() }).unwrap();
+        let record: Record = serde_json::from_str(&json).unwrap();
+        (json, record.id)
+    }
+
+    #[test]
+    fn reads_number_and_string_ids() {
+        let number: Record = serde_json::from_str(r#"{"id": 12345}"#).unwrap();
+        let string: Record =
+            serde_json::from_str(r#"{"id": "Queen/Bohemian Rhapsody.lrc"}"#).unwrap();
+
+        assert_eq!(number.id, "12345");
+        assert_eq!(string.id, "Queen/Bohemian Rhapsody.lrc");
+    }
+
+    #[test]
+    fn writes_numeric_ids_as_numbers() {
+        assert_eq!(round_trip("12345"), (r#"{"id":12345}"#.to_string(), "12345".to_string()));
+    }
+
+    #[test]
+    fn writes_other_ids_as_strings() {
+        let path = "Queen/Bohemian Rhapsody.lrc";
+
+        assert_eq!(round_trip(path), (format!(r#"{{"id":"{}"}}"#, path), path.to_string()));
+        assert_eq!(round_trip(""), (r#"{"id":""}"#.to_string(), String::new()));
+    }
+
+    #[test]
+    fn keeps_leading_zeros() {
+        assert_eq!(round_trip("007"), (r#"{"id":"007"}"#.to_string(), "007".to_string()));
+    }
+}